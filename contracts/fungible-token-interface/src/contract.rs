@@ -21,8 +21,10 @@ use stellar_tokens::fungible::Base;
 
 pub const OWNER: Symbol = symbol_short!("OWNER");
 pub const LAST_MINT: Symbol = symbol_short!("LAST_MINT");
+pub const MIN_MINT: Symbol = symbol_short!("MIN_MINT");
 pub const MINT_COOLDOWN: u64 = 86400; // 24 hours in seconds
 pub const MAX_MINT_AMOUNT: i128 = 1000_0000000000000000; // 1000 tokens (18 decimals)
+pub const DEFAULT_ALLOWANCE_LEDGERS: u32 = 17280; // ~1 day at 5s ledgers
 
 #[contract]
 pub struct ExampleContract;
@@ -34,6 +36,8 @@ pub enum ExampleContractError {
     Unauthorized = 1,
     ExceedsMaxMint = 2,
     MintCooldownActive = 3,
+    BelowMinMint = 4,
+    InvalidMinMint = 5,
 }
 
 #[contractimpl]
@@ -44,6 +48,11 @@ impl ExampleContract {
         e.storage().instance().set(&OWNER, &owner);
     }
 
+    /// Get the owner allowed to `admin_mint`, `pause` and `unpause`.
+    pub fn get_owner(e: &Env) -> Address {
+        e.storage().instance().get(&OWNER).expect("owner should be set")
+    }
+
     /// `TokenInterface` doesn't require implementing `total_supply()` because
     /// of the need for backwards compatibility with Stellar classic assets.
     pub fn total_supply(e: &Env) -> i128 {
@@ -61,6 +70,11 @@ impl ExampleContract {
         if amount > MAX_MINT_AMOUNT {
             panic_with_error!(e, ExampleContractError::ExceedsMaxMint);
         }
+
+        // Reject dust mints that would only start the cooldown
+        if amount < Self::get_min_mint_amount(e) {
+            panic_with_error!(e, ExampleContractError::BelowMinMint);
+        }
         
         // Check cooldown period
         let current_time = e.ledger().timestamp();
@@ -79,7 +93,8 @@ impl ExampleContract {
     }
 
     /// Owner-only mint function for administrative purposes.
-    /// Allows the owner to mint without restrictions.
+    /// Allows the owner to mint without restrictions and emits an
+    /// `admin_minted` event with `(account, amount)`.
     #[when_not_paused]
     pub fn admin_mint(e: &Env, account: Address, amount: i128) {
         // When `ownable` module is available,
@@ -89,6 +104,9 @@ impl ExampleContract {
         owner.require_auth();
 
         Base::mint(e, &account, amount);
+
+        // Distinguish privileged issuance from public mints for auditors.
+        e.events().publish((Symbol::new(e, "admin_minted"),), (account, amount));
     }
 
     /// Get the remaining cooldown time for an account.
@@ -112,10 +130,55 @@ impl ExampleContract {
         MAX_MINT_AMOUNT
     }
 
+    /// Get the minimum amount accepted by the public `mint`.
+    /// Returns 0 until the owner sets one.
+    pub fn get_min_mint_amount(e: &Env) -> i128 {
+        e.storage().instance().get(&MIN_MINT).unwrap_or(0)
+    }
+
+    /// Owner-only: set the minimum amount accepted by the public `mint`.
+    /// 0 disables the check; negative amounts are rejected.
+    pub fn set_min_mint_amount(e: &Env, amount: i128) {
+        // When `ownable` module is available,
+        // the following checks should be equivalent to:
+        // `ownable::only_owner(&e);`
+        let owner: Address = e.storage().instance().get(&OWNER).expect("owner should be set");
+        owner.require_auth();
+
+        if amount < 0 {
+            panic_with_error!(e, ExampleContractError::InvalidMinMint);
+        }
+        if amount > MAX_MINT_AMOUNT {
+            panic_with_error!(e, ExampleContractError::ExceedsMaxMint);
+        }
+
+        e.storage().instance().set(&MIN_MINT, &amount);
+    }
+
     /// Get the cooldown period in seconds.
     pub fn get_mint_cooldown_period(e: &Env) -> u64 {
         MINT_COOLDOWN
     }
+
+    /// Approve `spender` for `amount`, live for `DEFAULT_ALLOWANCE_LEDGERS`
+    /// from the current ledger, so callers can't pass an already-expired one.
+    pub fn approve_with_default_expiry(e: &Env, owner: Address, spender: Address, amount: i128) {
+        let live_until_ledger = e.ledger().sequence() + DEFAULT_ALLOWANCE_LEDGERS;
+        Base::approve(e, &owner, &spender, amount, live_until_ledger);
+    }
+
+    /// Get the default allowance lifetime in ledgers.
+    pub fn get_default_allowance_ledgers(e: &Env) -> u32 {
+        DEFAULT_ALLOWANCE_LEDGERS
+    }
+
+    /// Get an allowance together with its `live_until_ledger`, so wallets can
+    /// warn about allowances that are about to expire. The amount is 0 once
+    /// the allowance has expired.
+    pub fn allowance_info(e: &Env, owner: Address, spender: Address) -> (i128, u32) {
+        let allowance = Base::allowance_data(e, &owner, &spender);
+        (Base::allowance(e, &owner, &spender), allowance.live_until_ledger)
+    }
 }
 
 #[contractimpl]
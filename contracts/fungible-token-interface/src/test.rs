@@ -2,9 +2,12 @@
 
 extern crate std;
 
-use soroban_sdk::{testutils::Address as _, Address, Env, String};
+use soroban_sdk::{
+    testutils::{Address as _, Events, Ledger},
+    vec, Address, Env, IntoVal, String, Symbol,
+};
 
-use crate::contract::{ExampleContract, ExampleContractClient};
+use crate::contract::{ExampleContract, ExampleContractClient, DEFAULT_ALLOWANCE_LEDGERS};
 
 fn create_client<'a>(e: &Env, owner: &Address, initial_supply: i128) -> ExampleContractClient<'a> {
     let address = e.register(ExampleContract, (owner, initial_supply));
@@ -25,6 +28,15 @@ fn initial_state() {
     assert!(!client.paused());
 }
 
+#[test]
+fn get_owner_returns_constructor_owner() {
+    let e = Env::default();
+    let owner = Address::generate(&e);
+    let client = create_client(&e, &owner, 1000);
+
+    assert_eq!(client.get_owner(), owner);
+}
+
 #[test]
 fn transfer_works() {
     let e = Env::default();
@@ -66,6 +78,43 @@ fn transfer_from_works() {
     assert_eq!(client.balance(&recipient), 200);
 }
 
+#[test]
+fn approve_with_default_expiry_lasts_default_window() {
+    let e = Env::default();
+    // Keep the contract instance alive past the allowance window
+    e.ledger().with_mut(|l| l.min_persistent_entry_ttl = 2 * DEFAULT_ALLOWANCE_LEDGERS);
+    let owner = Address::generate(&e);
+    let spender = Address::generate(&e);
+    let client = create_client(&e, &owner, 1000);
+
+    e.mock_all_auths();
+    let start = e.ledger().sequence();
+    client.approve_with_default_expiry(&owner, &spender, &300);
+    assert_eq!(client.get_default_allowance_ledgers(), DEFAULT_ALLOWANCE_LEDGERS);
+
+    e.ledger().with_mut(|l| l.sequence_number = start + DEFAULT_ALLOWANCE_LEDGERS);
+    assert_eq!(client.allowance(&owner, &spender), 300);
+
+    e.ledger().with_mut(|l| l.sequence_number = start + DEFAULT_ALLOWANCE_LEDGERS + 1);
+    assert_eq!(client.allowance(&owner, &spender), 0);
+}
+
+#[test]
+fn allowance_info_returns_expiry() {
+    let e = Env::default();
+    let owner = Address::generate(&e);
+    let spender = Address::generate(&e);
+    let client = create_client(&e, &owner, 1000);
+
+    e.mock_all_auths();
+    client.approve(&owner, &spender, &300, &100);
+    assert_eq!(client.allowance_info(&owner, &spender), (300, 100));
+
+    client.approve_with_default_expiry(&owner, &spender, &50);
+    let expected = e.ledger().sequence() + DEFAULT_ALLOWANCE_LEDGERS;
+    assert_eq!(client.allowance_info(&owner, &spender), (50, expected));
+}
+
 #[test]
 #[should_panic(expected = "Error(Contract, #1000)")]
 fn transfer_from_fails_when_paused() {
@@ -92,6 +141,56 @@ fn mint_works() {
     assert_eq!(client.balance(&owner), 1500);
 }
 
+#[test]
+#[should_panic(expected = "Error(Contract, #4)")]
+fn mint_below_minimum_fails() {
+    let e = Env::default();
+    let owner = Address::generate(&e);
+    let client = create_client(&e, &owner, 1000);
+
+    e.mock_all_auths();
+    client.set_min_mint_amount(&100);
+    assert_eq!(client.get_min_mint_amount(), 100);
+    client.mint(&owner, &99);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #5)")]
+fn negative_min_mint_amount_fails() {
+    let e = Env::default();
+    let owner = Address::generate(&e);
+    let client = create_client(&e, &owner, 1000);
+
+    e.mock_all_auths();
+    client.set_min_mint_amount(&-1);
+}
+
+#[test]
+fn admin_mint_emits_event() {
+    let e = Env::default();
+    let owner = Address::generate(&e);
+    let recipient = Address::generate(&e);
+    let client = create_client(&e, &owner, 1000);
+
+    e.mock_all_auths();
+    client.admin_mint(&recipient, &250);
+
+    let events = e.events().all();
+    assert_eq!(
+        events.slice(events.len() - 1..),
+        vec![
+            &e,
+            (
+                client.address.clone(),
+                (Symbol::new(&e, "admin_minted"),).into_val(&e),
+                (recipient.clone(), 250_i128).into_val(&e),
+            )
+        ]
+    );
+    assert_eq!(client.total_supply(), 1250);
+    assert_eq!(client.balance(&recipient), 250);
+}
+
 #[test]
 #[should_panic(expected = "Error(Contract, #1000)")]
 fn mint_fails_when_paused() {
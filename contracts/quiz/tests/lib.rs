@@ -2,15 +2,42 @@
 extern crate std;
 
 use soroban_sdk::{
-    testutils::{Address as _},
-    Address, Env, String, Vec,
+    testutils::{Address as _, Events, IssuerFlags, Ledger},
+    contract, contractimpl, Address, BytesN, Env, Map, String, Symbol, TryFromVal, Val, Vec,
     token::{StellarAssetClient, TokenClient},
 };
 use quiz::{
     QuizRoomContract, QuizRoomContractClient,
-    PrizeAsset,
+    DistributionReceipt, EconomicConfig, MismatchPolicy, PrizeAsset, PrizeMode, PauseScope, QuizError, RoomHeader,
+    RoomOptions, RoomStatus,
 };
 
+// Minimal contract that looks like a token but reports implausible decimals
+#[contract]
+pub struct WideDecimalsToken;
+
+#[contractimpl]
+impl WideDecimalsToken {
+    pub fn decimals(_e: Env) -> u32 {
+        30
+    }
+}
+
+// Records the last on_room_ended callback it received
+#[contract]
+pub struct RoomEndedRecorder;
+
+#[contractimpl]
+impl RoomEndedRecorder {
+    pub fn on_room_ended(e: Env, room_id: u32, winners: Vec<Address>) {
+        e.storage().instance().set(&Symbol::new(&e, "last"), &(room_id, winners));
+    }
+
+    pub fn last(e: Env) -> Option<(u32, Vec<Address>)> {
+        e.storage().instance().get(&Symbol::new(&e, "last"))
+    }
+}
+
 // Test helper functions
 fn create_quiz_contract(e: &Env) -> (QuizRoomContractClient, Address) {
     let contract_id = e.register(QuizRoomContract, ());
@@ -76,6 +103,18 @@ fn mint_tokens_for_users(
     }
 }
 
+// Topics of each event `contract` published under `name`, in emission order
+fn events_named(e: &Env, contract: &Address, name: &str) -> std::vec::Vec<Vec<Val>> {
+    let name = Symbol::new(e, name);
+    e.events().all().iter()
+        .filter(|(addr, topics, _)| {
+            addr == contract
+                && topics.get(0).is_some_and(|t| Symbol::try_from_val(e, &t).is_ok_and(|s| s == name))
+        })
+        .map(|(_, topics, _)| topics)
+        .collect()
+}
+
 // Basic initialization tests
 #[test]
 fn test_contract_initialization() {
@@ -102,6 +141,19 @@ fn test_contract_initialization() {
     assert!(result.is_err());
 }
 
+#[test]
+fn event_schema_version_is_announced() {
+    let e = Env::default(); e.mock_all_auths();
+    let (c, contract_addr) = create_quiz_contract(&e);
+    c.initialize(&Address::generate(&e), &Address::generate(&e), &Address::generate(&e));
+
+    let schema = events_named(&e, &contract_addr, "schema");
+    assert_eq!(schema.len(), 1);
+    let version = u32::try_from_val(&e, &schema[0].get(1).unwrap()).unwrap();
+    assert_eq!(version, 1);
+    assert_eq!(c.get_event_schema_version(), version);
+}
+
 #[test]
 fn test_token_management() {
     let e = Env::default();
@@ -141,7 +193,7 @@ fn test_emergency_controls() {
     // Test emergency pause
     assert!(!contract.is_emergency_paused());
     
-    contract.emergency_pause();
+    contract.emergency_pause(&None, &None);
     assert!(contract.is_emergency_paused());
     
     // Test that operations fail when paused
@@ -158,7 +210,8 @@ fn test_emergency_controls() {
         &2000,
         &60,
         &Some(30),
-        &Some(10)
+        &Some(10),
+        &None
     );
     assert!(result.is_err());
     
@@ -186,7 +239,8 @@ fn test_pool_room_creation() {
         &2000, // 20% prize pool
         &60, // 60% first place
         &Some(30), // 30% second place
-        &Some(10) // 10% third place
+        &Some(10), // 10% third place
+        &None
     );
     
     // Verify room was created
@@ -218,7 +272,8 @@ fn test_pool_room_creation_validation() {
         &2000,
         &60,
         &Some(30),
-        &Some(10)
+        &Some(10),
+        &None
     );
     assert!(result.is_err());
     
@@ -232,7 +287,8 @@ fn test_pool_room_creation_validation() {
         &2600, // 26% (max is 25%)
         &60,
         &Some(30),
-        &Some(10)
+        &Some(10),
+        &None
     );
     assert!(result.is_err());
     
@@ -246,7 +302,8 @@ fn test_pool_room_creation_validation() {
         &2000,
         &60,
         &Some(30),
-        &Some(20) // 60 + 30 + 20 = 110%
+        &Some(20), // 60 + 30 + 20 = 110%
+        &None
     );
     assert!(result.is_err());
     
@@ -261,7 +318,8 @@ fn test_pool_room_creation_validation() {
         &2000,
         &60,
         &Some(30),
-        &Some(10)
+        &Some(10),
+        &None
     );
     assert!(result.is_err());
 }
@@ -301,7 +359,8 @@ fn test_asset_room_creation() {
         &token_address,
         &2000000, // 0.2 tokens entry fee
         &Some(300), // 3% host fee
-        &prizes
+        &prizes,
+        &3
     );
     
     // Verify room was created
@@ -341,7 +400,8 @@ fn test_player_joining() {
         &2000,
         &60,
         &Some(30),
-        &Some(10)
+        &Some(10),
+        &None
     );
     
     // Player 1 joins with extras
@@ -388,7 +448,7 @@ fn test_player_joining_validation() {
     mint_tokens_for_users(&e, &token_address, &[player1.clone(), player2.clone()], 10000000);
     
     // Create room
-    contract.init_pool_room(&1, &host, &token_address, &1000000, &Some(250), &2000, &100, &None, &None);
+    contract.init_pool_room(&1, &host, &token_address, &1000000, &Some(250), &2000, &100, &None, &None, &None);
     
     // Player 1 joins successfully
     contract.join_room(&1, &player1, &String::from_str(&e, "Player1"), &0);
@@ -429,7 +489,7 @@ fn test_room_completion_with_winners() {
     mint_tokens_for_users(&e, &token_address, &players, 10000000);
     
     // Create room
-    contract.init_pool_room(&1, &host, &token_address, &1000000, &Some(200), &2000, &50, &Some(30), &Some(20));
+    contract.init_pool_room(&1, &host, &token_address, &1000000, &Some(200), &2000, &50, &Some(30), &Some(20), &None);
     
     // Players join
     contract.join_room(&1, &player1, &String::from_str(&e, "Winner"), &0);
@@ -474,7 +534,7 @@ fn test_room_completion_by_screen_names() {
     mint_tokens_for_users(&e, &token_address, &[player1.clone(), player2.clone()], 10000000);
     
     // Create room
-    contract.init_pool_room(&1, &host, &token_address, &1000000, &None, &2000, &70, &Some(30), &None);
+    contract.init_pool_room(&1, &host, &token_address, &1000000, &None, &2000, &70, &Some(30), &None, &None);
     
     // Players join
     contract.join_room(&1, &player1, &String::from_str(&e, "Champion"), &0);
@@ -506,11 +566,13 @@ fn test_room_completion_validation() {
     let token_address = tokens.get(0).unwrap();
     
     // Create room
-    contract.init_pool_room(&1, &host, &token_address, &1000000, &None, &2000, &100, &None, &None);
+    contract.init_pool_room(&1, &host, &token_address, &1000000, &None, &2000, &100, &None, &None, &None);
     
-    // Test ending room with no players
+    // Test ending room with no players (should be cancelled instead)
     let result = contract.try_end_room(&1, &None, &None, &None);
-    assert!(result.is_err());
+    assert_eq!(result, Err(Ok(QuizError::EmptyRoomUseCancel)));
+    let result = contract.try_end_room_by_screen_names(&1, &None, &None, &None);
+    assert_eq!(result, Err(Ok(QuizError::EmptyRoomUseCancel)));
     
     // Add player
     mint_tokens_for_users(&e, &token_address, &[player1.clone()], 10000000);
@@ -546,6 +608,7 @@ fn test_financial_calculations() {
         &2500, // 25% prize pool
         &100, // 100% to winner
         &None,
+        &None,
         &None
     );
     
@@ -596,13 +659,13 @@ fn test_edge_cases() {
     let min_entry_fee = 1000000;
     
     // Test room with minimum entry fee
-    contract.init_pool_room(&1, &host, &token_address, &min_entry_fee, &None, &0, &100, &None, &None);
+    contract.init_pool_room(&1, &host, &token_address, &min_entry_fee, &None, &0, &100, &None, &None, &None);
     
     // Test room with zero host fee and zero prize pool (100% charity)
-    contract.init_pool_room(&2, &host, &token_address, &min_entry_fee, &None, &0, &100, &None, &None);
+    contract.init_pool_room(&2, &host, &token_address, &min_entry_fee, &None, &0, &100, &None, &None, &None);
     
     // Test room with maximum allowed fees
-    contract.init_pool_room(&3, &host, &token_address, &min_entry_fee, &Some(500), &2500, &100, &None, &None);
+    contract.init_pool_room(&3, &host, &token_address, &min_entry_fee, &Some(500), &2500, &100, &None, &None, &None);
     
     // Test single player room
     let player = Address::generate(&e);
@@ -642,7 +705,7 @@ fn test_extreme_edge_cases() {
     let min_fee = 1000000; // 0.1 tokens (from economic config)
     
     // Test room with absolute minimum settings
-    contract.init_pool_room(&1, &host, &token_address, &min_fee, &None, &0, &100, &None, &None);
+    contract.init_pool_room(&1, &host, &token_address, &min_fee, &None, &0, &100, &None, &None, &None);
     
     // Test single player scenario
     let player = Address::generate(&e);
@@ -665,10 +728,42 @@ fn token_disable_blocks_new_rooms() {
     assert!(!c.is_token_approved(&token));
 
     // cannot init a room with a disabled token
-    let r = c.try_init_pool_room(&1, &host, &token, &1_000_000, &None, &2000, &100, &None, &None);
+    let r = c.try_init_pool_room(&1, &host, &token, &1_000_000, &None, &2000, &100, &None, &None, &None);
     assert!(r.is_err());
 }
 
+#[test]
+fn removed_token_readded_within_grace_keeps_settings() {
+    let e = Env::default(); e.mock_all_auths();
+    let (c, _, _, _, tokens) = initialize_contract_with_tokens(&e);
+    let token = tokens.get(0).unwrap();
+    c.set_token_readd_grace(&100);
+    e.ledger().with_mut(|l| l.sequence_number = 1_000);
+
+    // disabled before the accidental removal
+    c.enable_disable_token(&token, &false);
+    c.remove_approved_token(&token);
+    assert!(c.get_removed_token(&token).is_some());
+    assert_eq!(c.try_purge_removed_token(&token), Err(Ok(QuizError::Unauthorized)));
+
+    e.ledger().with_mut(|l| l.sequence_number = 1_100);
+    c.add_approved_token(&token, &String::from_str(&e, "NEW"), &String::from_str(&e, "New Name"));
+    let info = c.get_approved_tokens().tokens.get(token.clone()).unwrap();
+    assert_eq!(info.symbol, String::from_str(&e, "USDC"));
+    assert_eq!(info.name, String::from_str(&e, "USD Coin"));
+    assert!(!info.enabled);
+    assert!(c.get_removed_token(&token).is_none());
+
+    // past the window the entry can be purged and a re-add starts fresh
+    c.remove_approved_token(&token);
+    e.ledger().with_mut(|l| l.sequence_number = 1_201);
+    c.purge_removed_token(&token);
+    assert!(c.get_removed_token(&token).is_none());
+    assert_eq!(c.try_purge_removed_token(&token), Err(Ok(QuizError::TokenNotFound)));
+    c.add_approved_token(&token, &String::from_str(&e, "NEW"), &String::from_str(&e, "New Name"));
+    assert!(c.is_token_approved(&token));
+}
+
 #[test]
 fn join_insufficient_balance() {
     let e = Env::default(); e.mock_all_auths();
@@ -677,7 +772,7 @@ fn join_insufficient_balance() {
     let player = Address::generate(&e);
     let token = tokens.get(0).unwrap();
 
-    c.init_pool_room(&1, &host, &token, &1_000_000, &None, &2000, &100, &None, &None);
+    c.init_pool_room(&1, &host, &token, &1_000_000, &None, &2000, &100, &None, &None, &None);
     // Mint less than entry fee
     mint_tokens_for_users(&e, &token, &[player.clone()], 900_000);
     let r = c.try_join_room(&1, &player, &String::from_str(&e, "P"), &0);
@@ -687,19 +782,33 @@ fn join_insufficient_balance() {
 #[test]
 fn rounding_remainder_goes_to_charity() {
     let e = Env::default(); e.mock_all_auths();
-    let (c, contract_addr, _, charity, tokens) = initialize_contract_with_tokens(&e);
+    let (c, contract_addr, _, _, tokens) = initialize_contract_with_tokens(&e);
+    let charity = c.get_charity_wallet();
     let host = Address::generate(&e);
-    let p = Address::generate(&e);
+    let p1 = Address::generate(&e);
+    let p2 = Address::generate(&e);
+    let p3 = Address::generate(&e);
     let token = tokens.get(0).unwrap();
     let t = TokenClient::new(&e, &token);
 
-    // Choose values likely to produce truncation dust
-    c.init_pool_room(&1, &host, &token, &1_000_001, &Some(123), &2000, &100, &None, &None);
-    mint_tokens_for_users(&e, &token, &[p.clone()], 1_000_001);
-    c.join_room(&1, &p, &String::from_str(&e, "P"), &0);
+    // Choose values likely to produce truncation dust: a lone winner would absorb it, so split three ways
+    c.init_pool_room(&1, &host, &token, &1_000_001, &Some(123), &2000, &50, &Some(30), &Some(20), &None);
+    mint_tokens_for_users(&e, &token, &[p1.clone(), p2.clone(), p3.clone()], 1_000_001);
+    c.join_room(&1, &p1, &String::from_str(&e, "P1"), &0);
+    c.join_room(&1, &p2, &String::from_str(&e, "P2"), &0);
+    c.join_room(&1, &p3, &String::from_str(&e, "P3"), &0);
 
     let charity_before = t.balance(&charity);
-    c.end_room(&1, &Some(p), &None, &None);
+    c.end_room(&1, &Some(p1), &Some(p2), &Some(p3));
+    let swept = events_named(&e, &contract_addr, "remainder_swept");
+
+    // Dust is whatever the fee and prize splits leave of the pool
+    let receipt = c.get_distribution_receipt(&1).unwrap();
+    let dust = 3_000_003 - receipt.platform - receipt.charity - receipt.host - receipt.prize;
+    assert!(dust > 0);
+    assert_eq!(swept.len(), 1);
+    assert_eq!(Address::try_from_val(&e, &swept[0].get(2).unwrap()).unwrap(), charity);
+    assert_eq!(i128::try_from_val(&e, &swept[0].get(3).unwrap()).unwrap(), dust);
 
     assert_eq!(t.balance(&contract_addr), 0); // contract drained
     let charity_after = t.balance(&charity);
@@ -729,7 +838,7 @@ fn atomic_update_rolls_back_on_error() {
     let token = tokens.get(0).unwrap();
 
     mint_tokens_for_users(&e, &token, &[a.clone(), b.clone()], 2_000_000);
-    c.init_pool_room(&1, &host, &token, &1_000_000, &None, &2000, &100, &None, &None);
+    c.init_pool_room(&1, &host, &token, &1_000_000, &None, &2000, &100, &None, &None, &None);
 
     c.join_room(&1, &a, &String::from_str(&e,"Dup"), &0);
     let r = c.try_join_room(&1, &b, &String::from_str(&e,"Dup"), &0); // duplicate name
@@ -748,9 +857,9 @@ fn paused_blocks_join_and_end() {
     let t = tokens.get(0).unwrap();
 
     mint_tokens_for_users(&e, &t, &[p.clone()], 2_000_000);
-    c.init_pool_room(&1, &host, &t, &1_000_000, &None, &2000, &100, &None, &None);
+    c.init_pool_room(&1, &host, &t, &1_000_000, &None, &2000, &100, &None, &None, &None);
 
-    c.emergency_pause();
+    c.emergency_pause(&None, &None);
 
     let r1 = c.try_join_room(&1, &p, &String::from_str(&e,"P"), &0);
     assert!(r1.is_err());
@@ -762,3 +871,2409 @@ fn paused_blocks_join_and_end() {
     c.join_room(&1, &p, &String::from_str(&e,"P"), &0); // now succeeds
 }
 
+
+#[test]
+fn pause_scope_all_blocks_everything() {
+    let e = Env::default(); e.mock_all_auths();
+    let (c, _, _, _, tokens) = initialize_contract_with_tokens(&e);
+    let host = Address::generate(&e);
+    let a = Address::generate(&e);
+    let b = Address::generate(&e);
+    let t = tokens.get(0).unwrap();
+
+    mint_tokens_for_users(&e, &t, &[a.clone(), b.clone()], 2_000_000);
+    c.init_pool_room(&1, &host, &t, &1_000_000, &None, &2000, &100, &None, &None, &None);
+    c.join_room(&1, &a, &String::from_str(&e,"A"), &0);
+
+    c.emergency_pause(&Some(PauseScope::All), &None);
+    assert_eq!(c.get_pause_scope(), Some(PauseScope::All));
+
+    let r = c.try_init_pool_room(&2, &host, &t, &1_000_000, &None, &2000, &100, &None, &None, &None);
+    assert_eq!(r, Err(Ok(QuizError::EmergencyPause)));
+    let r = c.try_join_room(&1, &b, &String::from_str(&e,"B"), &0);
+    assert_eq!(r, Err(Ok(QuizError::EmergencyPause)));
+    let r = c.try_end_room(&1, &Some(a.clone()), &None, &None);
+    assert_eq!(r, Err(Ok(QuizError::EmergencyPause)));
+}
+
+#[test]
+fn pause_scope_joins_only_lets_rooms_settle() {
+    let e = Env::default(); e.mock_all_auths();
+    let (c, _, _, _, tokens) = initialize_contract_with_tokens(&e);
+    let host = Address::generate(&e);
+    let a = Address::generate(&e);
+    let b = Address::generate(&e);
+    let t = tokens.get(0).unwrap();
+
+    mint_tokens_for_users(&e, &t, &[a.clone(), b.clone()], 2_000_000);
+    c.init_pool_room(&1, &host, &t, &1_000_000, &None, &2000, &100, &None, &None, &None);
+    c.join_room(&1, &a, &String::from_str(&e,"A"), &0);
+
+    c.emergency_pause(&Some(PauseScope::JoinsOnly), &None);
+
+    let r = c.try_init_pool_room(&2, &host, &t, &1_000_000, &None, &2000, &100, &None, &None, &None);
+    assert_eq!(r, Err(Ok(QuizError::EmergencyPause)));
+    let r = c.try_join_room(&1, &b, &String::from_str(&e,"B"), &0);
+    assert_eq!(r, Err(Ok(QuizError::EmergencyPause)));
+
+    // In-flight room can still be ended
+    c.end_room(&1, &Some(a.clone()), &None, &None);
+    assert!(c.get_room_config(&1).unwrap().ended());
+}
+
+#[test]
+fn pause_scope_ends_only_still_accepts_joins() {
+    let e = Env::default(); e.mock_all_auths();
+    let (c, _, _, _, tokens) = initialize_contract_with_tokens(&e);
+    let host = Address::generate(&e);
+    let a = Address::generate(&e);
+    let t = tokens.get(0).unwrap();
+
+    mint_tokens_for_users(&e, &t, &[a.clone()], 2_000_000);
+    c.init_pool_room(&1, &host, &t, &1_000_000, &None, &2000, &100, &None, &None, &None);
+
+    c.emergency_pause(&Some(PauseScope::EndsOnly), &None);
+
+    c.init_pool_room(&2, &host, &t, &1_000_000, &None, &2000, &100, &None, &None, &None);
+    c.join_room(&1, &a, &String::from_str(&e,"A"), &0);
+
+    let r = c.try_end_room(&1, &Some(a.clone()), &None, &None);
+    assert_eq!(r, Err(Ok(QuizError::EmergencyPause)));
+
+    c.emergency_unpause();
+    assert_eq!(c.get_pause_scope(), None);
+    c.end_room(&1, &Some(a), &None, &None);
+}
+
+#[test]
+fn extras_paid_in_separate_token() {
+    let e = Env::default(); e.mock_all_auths();
+    let (c, contract_addr, _, platform, tokens) = initialize_contract_with_tokens(&e);
+    let host = Address::generate(&e);
+    let a = Address::generate(&e);
+    let b = Address::generate(&e);
+    let fee_token = tokens.get(0).unwrap();
+    let extras_token = tokens.get(1).unwrap();
+    let fee_client = TokenClient::new(&e, &fee_token);
+    let extras_client = TokenClient::new(&e, &extras_token);
+
+    mint_tokens_for_users(&e, &fee_token, &[a.clone(), b.clone()], 10_000_000);
+    mint_tokens_for_users(&e, &extras_token, &[a.clone(), b.clone()], 10_000_000);
+
+    c.init_pool_room(&1, &host, &fee_token, &1_000_000, &None, &2000, &100, &None, &None,
+        &Some(RoomOptions { extras_token: Some(extras_token.clone()), ..Default::default() }));
+    assert_eq!(c.get_room_config(&1).unwrap().extras_token(), &Some(extras_token.clone()));
+
+    c.join_room(&1, &a, &String::from_str(&e, "A"), &500_000);
+    c.join_room(&1, &b, &String::from_str(&e, "B"), &0);
+
+    // Entry paid in the fee token, extras in the extras token
+    assert_eq!(fee_client.balance(&a), 9_000_000);
+    assert_eq!(extras_client.balance(&a), 9_500_000);
+    assert_eq!(extras_client.balance(&b), 10_000_000);
+    assert_eq!(fee_client.balance(&contract_addr), 2_000_000);
+    assert_eq!(extras_client.balance(&contract_addr), 500_000);
+
+    let (total_pool, entry_fees, extras_fees, _, _) = c.get_room_financials(&1).unwrap();
+    assert_eq!(total_pool, 2_000_000);
+    assert_eq!(entry_fees, 2_000_000);
+    assert_eq!(extras_fees, 500_000);
+
+    c.end_room(&1, &Some(a.clone()), &None, &None);
+
+    // Each token's pool is split on its own: 20% platform, 20% prize
+    assert_eq!(fee_client.balance(&contract_addr), 0);
+    assert_eq!(extras_client.balance(&contract_addr), 0);
+    assert_eq!(fee_client.balance(&platform), 400_000);
+    assert_eq!(extras_client.balance(&platform), 100_000);
+    assert_eq!(fee_client.balance(&a), 9_000_000 + 400_000);
+    assert_eq!(extras_client.balance(&a), 9_500_000 + 100_000);
+}
+
+#[test]
+fn allocation_headroom_before_charity_minimum() {
+    let e = Env::default(); e.mock_all_auths();
+    let (c, _, _, _, _) = initialize_contract_with_tokens(&e);
+
+    // 10000 - 2000 platform - 5000 min charity = 3000 to share between host and prize
+    assert_eq!(c.get_allocation_headroom(&0, &0), 3000);
+    assert_eq!(c.get_allocation_headroom(&250, &2000), 750);
+    assert_eq!(c.get_allocation_headroom(&500, &2500), 0);
+
+    let r = c.try_get_allocation_headroom(&500, &2600);
+    assert_eq!(r, Err(Ok(QuizError::ArithmeticUnderflow)));
+}
+
+#[test]
+fn extras_pool_pays_winners_only_from_extras() {
+    let e = Env::default(); e.mock_all_auths();
+    let (c, contract_addr, _, platform, tokens) = initialize_contract_with_tokens(&e);
+    let charity = c.get_charity_wallet();
+    let host = Address::generate(&e);
+    let p1 = Address::generate(&e);
+    let p2 = Address::generate(&e);
+    let t = tokens.get(0).unwrap();
+    let tc = TokenClient::new(&e, &t);
+    let options = RoomOptions { extras_prize_pool: true, ..Default::default() };
+
+    // Entries can't also reserve a prize share
+    let r = c.try_init_pool_room(&1, &host, &t, &1_000_000, &None, &1000, &60, &Some(40), &None, &Some(options.clone()));
+    assert_eq!(r, Err(Ok(QuizError::InvalidPrizePoolBps)));
+
+    mint_tokens_for_users(&e, &t, &[p1.clone(), p2.clone()], 10_000_000);
+    c.init_pool_room(&1, &host, &t, &1_000_000, &None, &0, &60, &Some(40), &None, &Some(options));
+    assert_eq!(c.get_room_config(&1).unwrap().prize_mode(), &PrizeMode::ExtrasPool);
+    c.join_room(&1, &p1, &String::from_str(&e, "P1"), &500_000);
+    c.join_room(&1, &p2, &String::from_str(&e, "P2"), &300_000);
+    assert_eq!(c.get_prize_pool_amount(&1), Some(800_000));
+
+    c.end_room(&1, &Some(p1.clone()), &Some(p2.clone()), &None);
+
+    // Winners split the 800_000 of extras; the 2_000_000 of entries go 20% platform, 80% charity
+    assert_eq!(tc.balance(&p1), 10_000_000 - 1_500_000 + 480_000);
+    assert_eq!(tc.balance(&p2), 10_000_000 - 1_300_000 + 320_000);
+    assert_eq!(tc.balance(&platform), 400_000);
+    assert_eq!(tc.balance(&charity), 1_600_000);
+    assert_eq!(tc.balance(&contract_addr), 0);
+}
+
+#[test]
+fn split_all_shares_prize_equally_on_draw() {
+    let e = Env::default(); e.mock_all_auths();
+    let (c, contract_addr, _, platform, tokens) = initialize_contract_with_tokens(&e);
+    let charity = c.get_charity_wallet();
+    let host = Address::generate(&e);
+    let p1 = Address::generate(&e);
+    let p2 = Address::generate(&e);
+    let p3 = Address::generate(&e);
+    let t = tokens.get(0).unwrap();
+    let tc = TokenClient::new(&e, &t);
+
+    mint_tokens_for_users(&e, &t, &[p1.clone(), p2.clone(), p3.clone()], 10_000_000);
+    c.init_pool_room(&1, &host, &t, &1_000_001, &Some(500), &2500, &50, &Some(30), &Some(20), &None);
+    c.join_room(&1, &p1, &String::from_str(&e, "P1"), &0);
+    c.join_room(&1, &p2, &String::from_str(&e, "P2"), &0);
+    c.join_room(&1, &p3, &String::from_str(&e, "P3"), &0);
+
+    c.end_room_split_all(&1);
+
+    // Pool of 3_000_003: 20% platform, 5% host, 50% charity, 750_002 prize split three ways
+    for p in [&p1, &p2, &p3] {
+        assert_eq!(tc.balance(p), 10_000_000 - 1_000_001 + 250_000);
+    }
+    assert_eq!(tc.balance(&platform), 600_000);
+    assert_eq!(tc.balance(&host), 150_000);
+    assert_eq!(tc.balance(&charity), 1_500_001 + 2);
+    assert_eq!(tc.balance(&contract_addr), 0);
+    assert_eq!(c.get_room_status(&1), RoomStatus::Ended);
+    assert_eq!(c.try_end_room_split_all(&1), Err(Ok(QuizError::RoomAlreadyEnded)));
+}
+
+#[test]
+fn lifetime_winnings_sum_across_rooms() {
+    let e = Env::default(); e.mock_all_auths();
+    let (c, _, _, _, tokens) = initialize_contract_with_tokens(&e);
+    let host = Address::generate(&e);
+    let p1 = Address::generate(&e);
+    let p2 = Address::generate(&e);
+    let t = tokens.get(0).unwrap();
+    let tc = TokenClient::new(&e, &t);
+
+    mint_tokens_for_users(&e, &t, &[p1.clone(), p2.clone()], 10_000_000);
+    for room_id in 1..=2u32 {
+        c.init_pool_room(&room_id, &host, &t, &1_000_000, &None, &2000, &100, &None, &None, &None);
+        c.join_room(&room_id, &p1, &String::from_str(&e, "P1"), &0);
+        c.join_room(&room_id, &p2, &String::from_str(&e, "P2"), &0);
+    }
+    let after_joins = tc.balance(&p1);
+    c.end_room(&1, &Some(p1.clone()), &None, &None);
+    c.end_room(&2, &Some(p1.clone()), &None, &None);
+    assert_eq!(c.get_lifetime_winnings(&p1, &t), tc.balance(&p1) - after_joins);
+    // Totals are kept per token
+    assert_eq!(c.get_lifetime_winnings(&p1, &tokens.get(1).unwrap()), 0);
+
+    c.init_pool_room(&3, &host, &t, &1_000_000, &None, &2000, &100, &None, &None, &None);
+    c.join_room(&3, &p2, &String::from_str(&e, "P2"), &0);
+    c.end_room(&3, &Some(p2.clone()), &None, &None);
+
+    let top = c.get_top_winners(&t, &5);
+    assert_eq!(top.len(), 2);
+    assert_eq!(top.get(0).unwrap(), (p1.clone(), c.get_lifetime_winnings(&p1, &t)));
+    assert_eq!(top.get(1).unwrap(), (p2.clone(), c.get_lifetime_winnings(&p2, &t)));
+    assert_eq!(c.get_top_winners(&t, &1).len(), 1);
+    assert_eq!(c.get_top_winners(&t, &u32::MAX).len(), 2);
+    assert_eq!(c.get_top_winners(&tokens.get(1).unwrap(), &5).len(), 0);
+}
+
+#[test]
+fn charity_preview_grows_with_fewer_winners() {
+    let e = Env::default(); e.mock_all_auths();
+    let (c, _, _, _, tokens) = initialize_contract_with_tokens(&e);
+    let charity = c.get_charity_wallet();
+    let host = Address::generate(&e);
+    let p1 = Address::generate(&e);
+    let p2 = Address::generate(&e);
+    let p3 = Address::generate(&e);
+    let t = tokens.get(0).unwrap();
+    let tc = TokenClient::new(&e, &t);
+
+    mint_tokens_for_users(&e, &t, &[p1.clone(), p2.clone(), p3.clone()], 10_000_000);
+    c.init_pool_room(&1, &host, &t, &1_000_001, &Some(123), &2000, &50, &Some(30), &Some(20), &None);
+    c.join_room(&1, &p1, &String::from_str(&e, "P1"), &0);
+    c.join_room(&1, &p2, &String::from_str(&e, "P2"), &0);
+    c.join_room(&1, &p3, &String::from_str(&e, "P3"), &0);
+
+    let one = c.preview_charity_amount(&1, &1).unwrap();
+    let three = c.preview_charity_amount(&1, &3).unwrap();
+    assert!(one > three);
+    assert!(c.preview_charity_amount(&2, &1).is_none());
+
+    let before = tc.balance(&charity);
+    c.end_room(&1, &Some(p1), &Some(p2), &Some(p3));
+    assert_eq!(tc.balance(&charity) - before, three);
+    assert!(c.preview_charity_amount(&1, &3).is_none());
+}
+
+#[test]
+fn distribution_receipt_matches_balances() {
+    let e = Env::default(); e.mock_all_auths();
+    let (c, _, _, platform, tokens) = initialize_contract_with_tokens(&e);
+    let charity = c.get_charity_wallet();
+    let host = Address::generate(&e);
+    let p1 = Address::generate(&e);
+    let p2 = Address::generate(&e);
+    let p3 = Address::generate(&e);
+    let t = tokens.get(0).unwrap();
+    let tc = TokenClient::new(&e, &t);
+
+    mint_tokens_for_users(&e, &t, &[p1.clone(), p2.clone(), p3.clone()], 10_000_000);
+    c.init_pool_room(&1, &host, &t, &1_000_001, &Some(123), &2000, &50, &Some(30), &Some(20), &None);
+    c.join_room(&1, &p1, &String::from_str(&e, "P1"), &0);
+    c.join_room(&1, &p2, &String::from_str(&e, "P2"), &333);
+    c.join_room(&1, &p3, &String::from_str(&e, "P3"), &0);
+    assert!(c.get_distribution_receipt(&1).is_none());
+
+    let players_before = tc.balance(&p1) + tc.balance(&p2) + tc.balance(&p3);
+    c.end_room(&1, &Some(p1.clone()), &Some(p2.clone()), &Some(p3.clone()));
+
+    let receipt = c.get_distribution_receipt(&1).unwrap();
+    let players_after = tc.balance(&p1) + tc.balance(&p2) + tc.balance(&p3);
+
+    assert_eq!(receipt.platform, tc.balance(&platform));
+    assert_eq!(receipt.host, tc.balance(&host));
+    assert_eq!(receipt.charity + receipt.remainder, tc.balance(&charity));
+    assert_eq!(receipt.prize, players_after - players_before);
+    assert_eq!(
+        receipt.platform + receipt.charity + receipt.host + receipt.prize + receipt.remainder,
+        c.get_room_config(&1).unwrap().total_pool()
+    );
+    assert_eq!(receipt.ledger, e.ledger().sequence());
+
+    // A retried end still fails, but the original outcome stays readable
+    assert!(c.try_end_room(&1, &Some(p1.clone()), &None, &None).is_err());
+    assert_eq!(c.get_distribution_receipt(&1), Some(receipt));
+}
+
+#[test]
+fn end_room_detailed_returns_receipt() {
+    let e = Env::default(); e.mock_all_auths();
+    let (c, _, _, platform, tokens) = initialize_contract_with_tokens(&e);
+    let charity = c.get_charity_wallet();
+    let host = Address::generate(&e);
+    let p1 = Address::generate(&e);
+    let p2 = Address::generate(&e);
+    let t = tokens.get(0).unwrap();
+    let tc = TokenClient::new(&e, &t);
+
+    mint_tokens_for_users(&e, &t, &[p1.clone(), p2.clone()], 10_000_000);
+    c.init_pool_room(&1, &host, &t, &1_000_001, &Some(123), &2000, &60, &Some(40), &None, &None);
+    c.join_room(&1, &p1, &String::from_str(&e, "P1"), &0);
+    c.join_room(&1, &p2, &String::from_str(&e, "P2"), &0);
+
+    let players_before = tc.balance(&p1) + tc.balance(&p2);
+    let receipts = c.end_room_detailed(&1, &Some(p1.clone()), &Some(p2.clone()), &None);
+    assert_eq!(receipts.len(), 1);
+    let (token, receipt) = receipts.get(0).unwrap();
+
+    assert_eq!(token, t);
+    assert_eq!(receipt.platform, tc.balance(&platform));
+    assert_eq!(receipt.host, tc.balance(&host));
+    assert_eq!(receipt.charity + receipt.remainder, tc.balance(&charity));
+    assert_eq!(receipt.prize, tc.balance(&p1) + tc.balance(&p2) - players_before);
+    assert_eq!(c.get_distribution_receipt(&1), Some(receipt));
+}
+
+#[test]
+fn end_room_detailed_reports_extras_pool() {
+    let e = Env::default(); e.mock_all_auths();
+    let (c, _, _, platform, tokens) = initialize_contract_with_tokens(&e);
+    let host = Address::generate(&e);
+    let a = Address::generate(&e);
+    let fee_token = tokens.get(0).unwrap();
+    let extras_token = tokens.get(1).unwrap();
+
+    mint_tokens_for_users(&e, &fee_token, &[a.clone()], 10_000_000);
+    mint_tokens_for_users(&e, &extras_token, &[a.clone()], 10_000_000);
+    c.init_pool_room(&1, &host, &fee_token, &1_000_000, &None, &2000, &100, &None, &None,
+        &Some(RoomOptions { extras_token: Some(extras_token.clone()), ..Default::default() }));
+    c.join_room(&1, &a, &String::from_str(&e, "A"), &500_000);
+
+    let receipts = c.end_room_detailed(&1, &Some(a.clone()), &None, &None);
+    assert_eq!(receipts.len(), 2);
+    let (fee_pool_token, fee_receipt) = receipts.get(0).unwrap();
+    let (extras_pool_token, extras_receipt) = receipts.get(1).unwrap();
+
+    assert_eq!(fee_pool_token, fee_token);
+    assert_eq!(fee_receipt.platform, TokenClient::new(&e, &fee_token).balance(&platform));
+    assert_eq!(extras_pool_token, extras_token);
+    assert_eq!(extras_receipt.platform, TokenClient::new(&e, &extras_token).balance(&platform));
+    assert_eq!(extras_receipt.prize, 100_000);
+}
+
+#[test]
+fn admin_corrects_room_charity_share() {
+    let e = Env::default(); e.mock_all_auths();
+    let (c, _, _, _, tokens) = initialize_contract_with_tokens(&e);
+    let host = Address::generate(&e);
+    let player = Address::generate(&e);
+    let t = tokens.get(0).unwrap();
+    mint_tokens_for_users(&e, &t, &[player.clone()], 10_000_000);
+    c.init_pool_room(&1, &host, &t, &1_000_000, &Some(300), &2000, &100, &None, &None, &None);
+    assert_eq!(c.get_effective_splits(&1), Some((2000, 300, 2000, 5700)));
+
+    // The host fee absorbs the change and must stay within its limits
+    assert_eq!(c.try_admin_set_room_charity_bps(&1, &4000), Err(Ok(QuizError::CharityBelowMinimum)));
+    assert_eq!(c.try_admin_set_room_charity_bps(&1, &5000), Err(Ok(QuizError::InvalidHostFee)));
+    assert_eq!(c.try_admin_set_room_charity_bps(&1, &6100), Err(Ok(QuizError::InvalidTotalAllocation)));
+
+    c.admin_set_room_charity_bps(&1, &5800);
+    assert_eq!(c.get_effective_splits(&1), Some((2000, 200, 2000, 5800)));
+
+    c.join_room(&1, &player, &String::from_str(&e, "P"), &0);
+    assert_eq!(c.try_admin_set_room_charity_bps(&1, &5900), Err(Ok(QuizError::PlayerAlreadyJoined)));
+}
+
+#[test]
+fn prize_pool_requires_full_distribution() {
+    let e = Env::default(); e.mock_all_auths();
+    let (c, _, _, _, tokens) = initialize_contract_with_tokens(&e);
+    let host = Address::generate(&e);
+    let t = tokens.get(0).unwrap();
+    c.init_pool_room(&1, &host, &t, &1_000_000, &None, &2000, &60, &Some(40), &None, &None);
+
+    // Clearing every rank while the pool is still reserved is rejected
+    assert_eq!(c.try_update_prize_split(&1, &0, &None, &None), Err(Ok(QuizError::MissingPrizePoolConfig)));
+    assert_eq!(c.try_update_prize_split(&1, &50, &Some(30), &None), Err(Ok(QuizError::MissingPrizePoolConfig)));
+    assert_eq!(c.try_update_prize_split(&1, &50, &None, &Some(50)), Err(Ok(QuizError::InvalidPrizeSplit)));
+    assert!(c.verify_room_consistency(&1));
+
+    c.update_prize_split(&1, &50, &Some(30), &Some(20));
+    assert!(c.verify_room_consistency(&1));
+}
+
+#[test]
+fn monotonic_split_rejects_inverted_ranks() {
+    let e = Env::default(); e.mock_all_auths();
+    let (c, _, _, _, tokens) = initialize_contract_with_tokens(&e);
+    let host = Address::generate(&e);
+    let t = tokens.get(0).unwrap();
+
+    // Off by default
+    c.init_pool_room(&1, &host, &t, &1_000_000, &None, &2000, &30, &Some(60), &Some(10), &None);
+
+    c.set_require_monotonic_split(&true);
+    assert!(c.get_economic_config().require_monotonic_split);
+    let r = c.try_init_pool_room(&2, &host, &t, &1_000_000, &None, &2000, &30, &Some(60), &Some(10), &None);
+    assert_eq!(r, Err(Ok(QuizError::InvalidPrizeSplit)));
+    c.init_pool_room(&2, &host, &t, &1_000_000, &None, &2000, &50, &Some(30), &Some(20), &None);
+    c.init_pool_room(&3, &host, &t, &1_000_000, &None, &2000, &40, &Some(40), &Some(20), &None);
+
+    assert_eq!(c.try_update_prize_split(&2, &20, &Some(30), &Some(50)), Err(Ok(QuizError::InvalidPrizeSplit)));
+    c.update_prize_split(&2, &60, &Some(40), &None);
+}
+
+#[test]
+fn extras_must_match_increment() {
+    let e = Env::default(); e.mock_all_auths();
+    let (c, _, _, _, tokens) = initialize_contract_with_tokens(&e);
+    let host = Address::generate(&e);
+    let a = Address::generate(&e);
+    let b = Address::generate(&e);
+    let t = tokens.get(0).unwrap();
+
+    mint_tokens_for_users(&e, &t, &[a.clone(), b.clone()], 10_000_000);
+
+    // Increment must be positive
+    let bad = RoomOptions { extras_increment: Some(0), ..Default::default() };
+    let r = c.try_init_pool_room(&1, &host, &t, &1_000_000, &None, &2000, &100, &None, &None, &Some(bad));
+    assert_eq!(r, Err(Ok(QuizError::InvalidExtrasIncrement)));
+
+    // Extras sold in 0.05 token units
+    let options = RoomOptions { extras_increment: Some(500_000), ..Default::default() };
+    c.init_pool_room(&1, &host, &t, &1_000_000, &None, &2000, &100, &None, &None, &Some(options));
+    assert_eq!(c.get_room_config(&1).unwrap().extras_increment(), Some(500_000));
+
+    let r = c.try_join_room(&1, &a, &String::from_str(&e, "A"), &700_000);
+    assert_eq!(r, Err(Ok(QuizError::InvalidExtrasIncrement)));
+
+    c.join_room(&1, &a, &String::from_str(&e, "A"), &1_000_000);
+    c.join_room(&1, &b, &String::from_str(&e, "B"), &0);
+    assert_eq!(c.get_room_config(&1).unwrap().total_pool(), 3_000_000);
+}
+
+#[test]
+fn room_financials_batch_keeps_order() {
+    let e = Env::default(); e.mock_all_auths();
+    let (c, _, _, _, tokens) = initialize_contract_with_tokens(&e);
+    let host = Address::generate(&e);
+    let p = Address::generate(&e);
+    let t = tokens.get(0).unwrap();
+
+    mint_tokens_for_users(&e, &t, &[p.clone()], 10_000_000);
+
+    c.init_pool_room(&1, &host, &t, &1_000_000, &None, &2000, &100, &None, &None, &None);
+    c.init_pool_room(&2, &host, &t, &2_000_000, &None, &2000, &100, &None, &None, &None);
+    c.join_room(&2, &p, &String::from_str(&e, "P"), &500_000);
+
+    let batch = c.get_room_financials_batch(&Vec::from_array(&e, [2, 99, 1]));
+    assert_eq!(batch.len(), 3);
+    assert_eq!(batch.get(0).unwrap(), c.get_room_financials(&2));
+    assert_eq!(batch.get(0).unwrap().unwrap().0, 2_500_000);
+    assert_eq!(batch.get(1).unwrap(), None);
+    assert_eq!(batch.get(2).unwrap(), c.get_room_financials(&1));
+}
+
+#[test]
+fn redistribute_gives_missing_rank_share_to_winners() {
+    let e = Env::default(); e.mock_all_auths();
+    let (c, _, _, _, tokens) = initialize_contract_with_tokens(&e);
+    let host = Address::generate(&e);
+    let p1 = Address::generate(&e);
+    let p2 = Address::generate(&e);
+    let p3 = Address::generate(&e);
+    let t = tokens.get(0).unwrap();
+    let tc = TokenClient::new(&e, &t);
+
+    mint_tokens_for_users(&e, &t, &[p1.clone(), p2.clone(), p3.clone()], 10_000_000);
+
+    let options = RoomOptions { mismatch_policy: MismatchPolicy::Redistribute, ..Default::default() };
+    c.init_pool_room(&1, &host, &t, &1_000_000, &None, &2000, &50, &Some(30), &Some(20), &Some(options));
+    c.join_room(&1, &p1, &String::from_str(&e, "P1"), &0);
+    c.join_room(&1, &p2, &String::from_str(&e, "P2"), &0);
+    c.join_room(&1, &p3, &String::from_str(&e, "P3"), &0);
+
+    // Only two of the three ranks are filled
+    c.end_room(&1, &Some(p1.clone()), &Some(p2.clone()), &None);
+
+    // Prize pool is 20% of 3_000_000, re-weighted 50:30 across the two winners
+    assert_eq!(tc.balance(&p1), 9_000_000 + 375_000);
+    assert_eq!(tc.balance(&p2), 9_000_000 + 225_000);
+
+    let receipt = c.get_distribution_receipt(&1).unwrap();
+    assert_eq!(receipt.prize, 600_000);
+    assert_eq!(receipt.remainder, 0);
+}
+
+#[test]
+fn asset_escrow_tracked_apart_from_fees_in_same_token() {
+    let e = Env::default(); e.mock_all_auths();
+    let (c, contract_addr, _, _, tokens) = initialize_contract_with_tokens(&e);
+    let host = Address::generate(&e);
+    let p1 = Address::generate(&e);
+    let p2 = Address::generate(&e);
+    let t = tokens.get(0).unwrap();
+    let tc = TokenClient::new(&e, &t);
+
+    mint_tokens_for_users(&e, &t, &[host.clone(), p1.clone(), p2.clone()], 10_000_000);
+
+    // Prize escrowed in the same token the room charges fees in
+    let prizes = Vec::from_array(&e, [PrizeAsset { contract_id: t.clone(), amount: 4_000_000 }]);
+    c.init_asset_room(&1, &host, &t, &1_000_000, &None, &prizes, &1);
+    c.join_room(&1, &p1, &String::from_str(&e, "P1"), &0);
+    c.join_room(&1, &p2, &String::from_str(&e, "P2"), &250_000);
+
+    let pool = c.get_room_config(&1).unwrap().total_pool();
+    assert_eq!(pool, 2_250_000);
+    assert_eq!(c.get_escrowed_amount(&t), 4_000_000);
+    assert_eq!(tc.balance(&contract_addr), c.get_escrowed_amount(&t) + pool);
+
+    c.end_room(&1, &Some(p1.clone()), &None, &None);
+
+    assert_eq!(c.get_escrowed_amount(&t), 0);
+    assert_eq!(tc.balance(&contract_addr), 0);
+}
+
+#[test]
+fn room_audit_records_joins_then_end() {
+    let e = Env::default(); e.mock_all_auths();
+    let (c, _, _, _, tokens) = initialize_contract_with_tokens(&e);
+    let host = Address::generate(&e);
+    let p1 = Address::generate(&e);
+    let p2 = Address::generate(&e);
+    let t = tokens.get(0).unwrap();
+
+    mint_tokens_for_users(&e, &t, &[p1.clone(), p2.clone()], 10_000_000);
+
+    c.init_pool_room(&1, &host, &t, &1_000_000, &None, &2000, &100, &None, &None, &None);
+    c.join_room(&1, &p1, &String::from_str(&e, "P1"), &0);
+    c.join_room(&1, &p2, &String::from_str(&e, "P2"), &0);
+    c.end_room(&1, &Some(p1.clone()), &None, &None);
+
+    let audit = c.get_room_audit(&1);
+    let ledger = e.ledger().sequence();
+    assert_eq!(audit.len(), 4);
+    assert_eq!(audit.get(0).unwrap(), (Symbol::new(&e, "create"), host.clone(), ledger));
+    assert_eq!(audit.get(1).unwrap(), (Symbol::new(&e, "join"), p1.clone(), ledger));
+    assert_eq!(audit.get(2).unwrap(), (Symbol::new(&e, "join"), p2.clone(), ledger));
+    assert_eq!(audit.get(3).unwrap(), (Symbol::new(&e, "end"), host.clone(), ledger));
+
+    assert_eq!(c.get_room_audit(&2).len(), 0);
+}
+
+#[test]
+fn recompute_room_totals_keeps_consistent_room_unchanged() {
+    let e = Env::default(); e.mock_all_auths();
+    let (c, _, _, _, tokens) = initialize_contract_with_tokens(&e);
+    let host = Address::generate(&e);
+    let p1 = Address::generate(&e);
+    let p2 = Address::generate(&e);
+    let t = tokens.get(0).unwrap();
+
+    mint_tokens_for_users(&e, &t, &[p1.clone(), p2.clone()], 10_000_000);
+
+    c.init_pool_room(&1, &host, &t, &1_000_000, &None, &2000, &100, &None, &None, &None);
+    c.join_room(&1, &p1, &String::from_str(&e, "P1"), &300_000);
+    c.join_room(&1, &p2, &String::from_str(&e, "P2"), &0);
+
+    let before = c.get_room_financials(&1).unwrap();
+    c.recompute_room_totals(&1);
+    assert_eq!(c.get_room_financials(&1).unwrap(), before);
+    assert_eq!(c.get_room_config(&1).unwrap().player_count(), 2);
+
+    assert_eq!(c.try_recompute_room_totals(&99), Err(Ok(QuizError::RoomNotFound)));
+
+    c.end_room(&1, &Some(p1.clone()), &None, &None);
+    assert_eq!(c.try_recompute_room_totals(&1), Err(Ok(QuizError::RoomAlreadyEnded)));
+}
+
+#[test]
+fn host_fee_can_boost_prize_pool() {
+    let e = Env::default(); e.mock_all_auths();
+    let (c, _, _, _, tokens) = initialize_contract_with_tokens(&e);
+    let host = Address::generate(&e);
+    let p1 = Address::generate(&e);
+    let p2 = Address::generate(&e);
+    let t = tokens.get(0).unwrap();
+    let tc = TokenClient::new(&e, &t);
+
+    mint_tokens_for_users(&e, &t, &[p1.clone(), p2.clone()], 10_000_000);
+
+    let options = RoomOptions { host_fee_to_prize: true, ..Default::default() };
+    c.init_pool_room(&1, &host, &t, &1_000_000, &Some(250), &2000, &100, &None, &None, &Some(options));
+    c.join_room(&1, &p1, &String::from_str(&e, "P1"), &0);
+    c.join_room(&1, &p2, &String::from_str(&e, "P2"), &0);
+
+    c.end_room(&1, &Some(p1.clone()), &None, &None);
+
+    // 20% prize share of 2_000_000 plus the 2.5% host fee
+    assert_eq!(tc.balance(&p1), 9_000_000 + 400_000 + 50_000);
+    assert_eq!(tc.balance(&host), 0);
+
+    let receipt = c.get_distribution_receipt(&1).unwrap();
+    assert_eq!(receipt.host, 0);
+    assert_eq!(receipt.prize, 450_000);
+    assert_eq!(receipt.platform + receipt.charity + receipt.prize + receipt.remainder, 2_000_000);
+}
+
+#[test]
+fn host_room_limit_blocks_second_concurrent_room() {
+    let e = Env::default(); e.mock_all_auths();
+    let (c, _, _, _, tokens) = initialize_contract_with_tokens(&e);
+    let host = Address::generate(&e);
+    let other_host = Address::generate(&e);
+    let p = Address::generate(&e);
+    let t = tokens.get(0).unwrap();
+
+    mint_tokens_for_users(&e, &t, &[p.clone()], 10_000_000);
+    c.set_max_rooms_per_host(&1);
+
+    c.init_pool_room(&1, &host, &t, &1_000_000, &None, &2000, &100, &None, &None, &None);
+    assert_eq!(c.get_host_active_room_count(&host), 1);
+
+    let r = c.try_init_pool_room(&2, &host, &t, &1_000_000, &None, &2000, &100, &None, &None, &None);
+    assert_eq!(r, Err(Ok(QuizError::HostRoomLimitReached)));
+
+    // The limit is per host
+    c.init_pool_room(&3, &other_host, &t, &1_000_000, &None, &2000, &100, &None, &None, &None);
+
+    // Ending the room frees the slot
+    c.join_room(&1, &p, &String::from_str(&e, "P"), &0);
+    c.end_room(&1, &Some(p.clone()), &None, &None);
+    assert_eq!(c.get_host_active_room_count(&host), 0);
+    c.init_pool_room(&2, &host, &t, &1_000_000, &None, &2000, &100, &None, &None, &None);
+}
+
+#[test]
+fn resolve_winners_by_join_order_sorts_earliest_first() {
+    let e = Env::default(); e.mock_all_auths();
+    let (c, _, _, _, tokens) = initialize_contract_with_tokens(&e);
+    let host = Address::generate(&e);
+    let early = Address::generate(&e);
+    let late = Address::generate(&e);
+    let t = tokens.get(0).unwrap();
+
+    mint_tokens_for_users(&e, &t, &[early.clone(), late.clone()], 10_000_000);
+    c.init_pool_room(&1, &host, &t, &1_000_000, &None, &2000, &60, &Some(40), &None, &None);
+
+    c.join_room(&1, &early, &String::from_str(&e, "Early"), &0);
+    e.ledger().with_mut(|l| l.sequence_number += 10);
+    c.join_room(&1, &late, &String::from_str(&e, "Late"), &0);
+
+    let names = Vec::from_array(&e, [
+        String::from_str(&e, "Late"),
+        String::from_str(&e, "Nobody"),
+        String::from_str(&e, "Early"),
+    ]);
+    let ordered = c.resolve_winners_by_join_order(&1, &names);
+    assert_eq!(ordered, Vec::from_array(&e, [early.clone(), late.clone()]));
+}
+
+#[test]
+fn dust_prize_share_is_rejected() {
+    let e = Env::default(); e.mock_all_auths();
+    let (c, contract_addr, _, _, tokens) = initialize_contract_with_tokens(&e);
+    let host = Address::generate(&e);
+    let p1 = Address::generate(&e);
+    let p2 = Address::generate(&e);
+    let t = tokens.get(0).unwrap();
+
+    // Drop the entry fee floor so a tiny room can be created
+    e.as_contract(&contract_addr, || {
+        let key = Symbol::new(&e, "econ_cfg");
+        let mut cfg: EconomicConfig = e.storage().instance().get(&key).unwrap();
+        cfg.min_entry_fee = 1;
+        e.storage().instance().set(&key, &cfg);
+    });
+
+    mint_tokens_for_users(&e, &t, &[p1.clone(), p2.clone()], 100);
+
+    // Two players make a prize pool of 2, which gives second place 10% = 0
+    let r = c.try_init_pool_room(&1, &host, &t, &5, &None, &2000, &90, &Some(10), &None, &None);
+    assert_eq!(r, Err(Ok(QuizError::PrizeTooSmall)));
+
+    // A 1% prize pool leaves the lone winner of a 50-unit room nothing; the rest goes to charity
+    let r = c.try_init_pool_room(&1, &host, &t, &50, &None, &100, &100, &None, &None, &None);
+    assert_eq!(r, Err(Ok(QuizError::PrizeTooSmall)));
+
+    c.init_pool_room(&1, &host, &t, &5, &None, &2000, &100, &None, &None, &None);
+    assert_eq!(c.try_update_prize_split(&1, &90, &Some(10), &None), Err(Ok(QuizError::PrizeTooSmall)));
+    c.join_room(&1, &p1, &String::from_str(&e, "P1"), &0);
+    c.end_room(&1, &Some(p1.clone()), &None, &None);
+    assert!(c.get_room_config(&1).unwrap().ended());
+}
+
+#[test]
+fn approve_tokens_in_one_batch() {
+    let e = Env::default(); e.mock_all_auths();
+    let (c, _, admin, _, _) = initialize_contract_with_tokens(&e);
+    let a = create_token_contract(&e, &admin);
+    let b = create_token_contract(&e, &admin);
+    let d = create_token_contract(&e, &admin);
+
+    c.add_approved_tokens_batch(&Vec::from_array(&e, [
+        (a.clone(), String::from_str(&e, "AAA"), String::from_str(&e, "Token A")),
+        (b.clone(), String::from_str(&e, "BBB"), String::from_str(&e, "Token B")),
+        (d.clone(), String::from_str(&e, "DDD"), String::from_str(&e, "Token D")),
+    ]));
+    assert_eq!(c.get_approved_tokens_list().len(), 6);
+    assert!(c.is_token_approved(&d));
+
+    // A duplicate anywhere in the batch rejects all of it
+    let fresh = create_token_contract(&e, &admin);
+    let r = c.try_add_approved_tokens_batch(&Vec::from_array(&e, [
+        (fresh.clone(), String::from_str(&e, "NEW"), String::from_str(&e, "Fresh")),
+        (a.clone(), String::from_str(&e, "AAA"), String::from_str(&e, "Token A")),
+    ]));
+    assert_eq!(r, Err(Ok(QuizError::TokenAlreadyExists)));
+    assert!(!c.is_token_approved(&fresh));
+    assert_eq!(c.get_approved_tokens_list().len(), 6);
+}
+
+#[test]
+fn asset_room_rejects_more_winners_than_prizes() {
+    let e = Env::default(); e.mock_all_auths();
+    let (c, _, _, _, tokens) = initialize_contract_with_tokens(&e);
+    let host = Address::generate(&e);
+    let p1 = Address::generate(&e);
+    let p2 = Address::generate(&e);
+    let fee = tokens.get(0).unwrap();
+    let prize = tokens.get(1).unwrap();
+
+    mint_tokens_for_users(&e, &prize, &[host.clone()], 5_000_000);
+    mint_tokens_for_users(&e, &fee, &[p1.clone(), p2.clone()], 10_000_000);
+
+    let prizes = Vec::from_array(&e, [PrizeAsset { contract_id: prize.clone(), amount: 5_000_000 }]);
+    c.init_asset_room(&1, &host, &fee, &1_000_000, &None, &prizes, &1);
+    c.join_room(&1, &p1, &String::from_str(&e, "P1"), &0);
+    c.join_room(&1, &p2, &String::from_str(&e, "P2"), &0);
+
+    let r = c.try_end_room(&1, &Some(p1.clone()), &Some(p2.clone()), &None);
+    assert_eq!(r, Err(Ok(QuizError::InvalidWinners)));
+
+    c.end_room(&1, &Some(p1.clone()), &None, &None);
+    assert_eq!(TokenClient::new(&e, &prize).balance(&p1), 5_000_000);
+}
+
+#[test]
+fn pool_room_converts_to_asset_room_before_joins() {
+    let e = Env::default(); e.mock_all_auths();
+    let (c, contract_addr, _, platform, tokens) = initialize_contract_with_tokens(&e);
+    let charity = c.get_charity_wallet();
+    let host = Address::generate(&e);
+    let p1 = Address::generate(&e);
+    let p2 = Address::generate(&e);
+    let fee = tokens.get(0).unwrap();
+    let prize = tokens.get(1).unwrap();
+    let fc = TokenClient::new(&e, &fee);
+
+    mint_tokens_for_users(&e, &prize, &[host.clone()], 5_000_000);
+    mint_tokens_for_users(&e, &fee, &[p1.clone(), p2.clone()], 10_000_000);
+    c.init_pool_room(&1, &host, &fee, &1_000_000, &None, &2000, &60, &Some(40), &None, &None);
+
+    let prizes = Vec::from_array(&e, [PrizeAsset { contract_id: prize.clone(), amount: 5_000_000 }]);
+    c.convert_to_asset_room(&1, &prizes);
+    let config = c.get_room_config(&1).unwrap();
+    assert_eq!(config.prize_mode(), &PrizeMode::AssetBased);
+    assert_eq!(config.prize_pool_bps(), 0);
+    assert_eq!(c.get_effective_splits(&1), Some((2000, 0, 0, 8000)));
+    assert_eq!(TokenClient::new(&e, &prize).balance(&contract_addr), 5_000_000);
+    assert_eq!(c.try_convert_to_asset_room(&1, &prizes), Err(Ok(QuizError::MissingPrizePoolConfig)));
+
+    c.join_room(&1, &p1, &String::from_str(&e, "P1"), &0);
+    c.join_room(&1, &p2, &String::from_str(&e, "P2"), &0);
+    c.end_room(&1, &Some(p1.clone()), &None, &None);
+
+    assert_eq!(TokenClient::new(&e, &prize).balance(&p1), 5_000_000);
+    assert_eq!(fc.balance(&platform), 400_000);
+    assert_eq!(fc.balance(&charity), 1_600_000);
+    assert_eq!(fc.balance(&contract_addr), 0);
+}
+
+#[test]
+fn converting_a_joined_room_is_rejected() {
+    let e = Env::default(); e.mock_all_auths();
+    let (c, _, _, _, tokens) = initialize_contract_with_tokens(&e);
+    let host = Address::generate(&e);
+    let p1 = Address::generate(&e);
+    let fee = tokens.get(0).unwrap();
+    let prize = tokens.get(1).unwrap();
+
+    mint_tokens_for_users(&e, &prize, &[host.clone()], 5_000_000);
+    mint_tokens_for_users(&e, &fee, &[p1.clone()], 10_000_000);
+    c.init_pool_room(&1, &host, &fee, &1_000_000, &None, &2000, &100, &None, &None, &None);
+    c.join_room(&1, &p1, &String::from_str(&e, "P1"), &0);
+
+    let prizes = Vec::from_array(&e, [PrizeAsset { contract_id: prize.clone(), amount: 5_000_000 }]);
+    assert_eq!(c.try_convert_to_asset_room(&1, &prizes), Err(Ok(QuizError::PlayerAlreadyJoined)));
+    assert_eq!(TokenClient::new(&e, &prize).balance(&host), 5_000_000);
+}
+
+#[test]
+fn reduced_platform_fee_goes_to_charity() {
+    let e = Env::default(); e.mock_all_auths();
+    let (c, _, _, platform, tokens) = initialize_contract_with_tokens(&e);
+    let charity = c.get_charity_wallet();
+    let host = Address::generate(&e);
+    let p1 = Address::generate(&e);
+    let p2 = Address::generate(&e);
+    let t = tokens.get(0).unwrap();
+    let tc = TokenClient::new(&e, &t);
+
+    mint_tokens_for_users(&e, &t, &[p1.clone(), p2.clone()], 10_000_000);
+
+    // Can't raise the platform fee above the global 20%
+    let too_high = RoomOptions { platform_fee_bps_override: Some(2500), ..Default::default() };
+    let r = c.try_init_pool_room(&1, &host, &t, &1_000_000, &None, &2000, &100, &None, &None, &Some(too_high));
+    assert_eq!(r, Err(Ok(QuizError::PercentageTooHigh)));
+
+    let options = RoomOptions { platform_fee_bps_override: Some(1000), ..Default::default() };
+    c.init_pool_room(&1, &host, &t, &1_000_000, &None, &2000, &100, &None, &None, &Some(options));
+    assert_eq!(c.get_room_config(&1).unwrap().platform_fee_bps(), 1000);
+
+    c.join_room(&1, &p1, &String::from_str(&e, "P1"), &0);
+    c.join_room(&1, &p2, &String::from_str(&e, "P2"), &0);
+    c.end_room(&1, &Some(p1.clone()), &None, &None);
+
+    // 10% platform instead of 20%; charity takes 70% instead of 60%
+    assert_eq!(tc.balance(&platform), 200_000);
+    assert_eq!(tc.balance(&charity), 1_400_000);
+    assert_eq!(tc.balance(&p1), 9_000_000 + 400_000);
+}
+
+#[test]
+fn pause_info_tracks_when_pause_began() {
+    let e = Env::default(); e.mock_all_auths();
+    let (c, _, _, _, _) = initialize_contract_with_tokens(&e);
+    assert_eq!(c.get_pause_info(), None);
+
+    e.ledger().with_mut(|l| { l.sequence_number = 100; l.timestamp = 5_000; });
+    c.emergency_pause(&Some(PauseScope::JoinsOnly), &None);
+    assert_eq!(c.get_pause_info(), Some((100, 5_000)));
+
+    // Widening the scope later keeps the original start
+    e.ledger().with_mut(|l| { l.sequence_number = 120; l.timestamp = 6_000; });
+    c.emergency_pause(&None, &None);
+    assert_eq!(c.get_pause_info(), Some((100, 5_000)));
+
+    c.emergency_unpause();
+    assert_eq!(c.get_pause_info(), None);
+}
+
+#[test]
+fn effective_splits_sum_to_whole() {
+    let e = Env::default(); e.mock_all_auths();
+    let (c, _, _, _, tokens) = initialize_contract_with_tokens(&e);
+    let host = Address::generate(&e);
+    let t = tokens.get(0).unwrap();
+
+    c.init_pool_room(&1, &host, &t, &1_000_000, &Some(300), &1500, &100, &None, &None, &None);
+
+    let (platform, host_fee, prize, charity) = c.get_effective_splits(&1).unwrap();
+    assert_eq!((platform, host_fee, prize, charity), (2000, 300, 1500, 6200));
+    assert_eq!(platform + host_fee + prize + charity, 10000);
+    assert_eq!(c.get_effective_splits(&2), None);
+}
+
+#[test]
+fn extended_join_window_admits_late_players() {
+    let e = Env::default(); e.mock_all_auths();
+    let (c, _, _, _, tokens) = initialize_contract_with_tokens(&e);
+    let host = Address::generate(&e);
+    let a = Address::generate(&e);
+    let b = Address::generate(&e);
+    let t = tokens.get(0).unwrap();
+
+    mint_tokens_for_users(&e, &t, &[a.clone(), b.clone()], 10_000_000);
+    e.ledger().with_mut(|l| l.sequence_number = 100);
+
+    let options = RoomOptions { join_close_ledger: Some(110), ..Default::default() };
+    c.init_pool_room(&1, &host, &t, &1_000_000, &None, &2000, &100, &None, &None, &Some(options));
+
+    // Only extensions are allowed
+    assert_eq!(c.try_extend_join_window(&1, &105), Err(Ok(QuizError::InvalidJoinWindow)));
+    c.extend_join_window(&1, &150);
+    assert_eq!(c.get_room_config(&1).unwrap().join_close_ledger(), Some(150));
+
+    // Past the original close but inside the extended window
+    e.ledger().with_mut(|l| l.sequence_number = 120);
+    c.join_room(&1, &a, &String::from_str(&e, "A"), &0);
+
+    // Once closed, joins and further extensions are refused
+    e.ledger().with_mut(|l| l.sequence_number = 151);
+    let r = c.try_join_room(&1, &b, &String::from_str(&e, "B"), &0);
+    assert_eq!(r, Err(Ok(QuizError::JoinWindowClosed)));
+    assert_eq!(c.try_extend_join_window(&1, &200), Err(Ok(QuizError::JoinWindowClosed)));
+}
+
+// Integration tests link the library without cfg(test), so this exercises the strict token check
+#[test]
+fn strict_token_check_rejects_non_tokens() {
+    let e = Env::default(); e.mock_all_auths();
+    let (c, _, _, _, _) = initialize_contract_with_tokens(&e);
+
+    // A contract with no decimals() at all
+    let (_, not_a_token) = create_quiz_contract(&e);
+    let r = c.try_add_approved_token(&not_a_token, &String::from_str(&e, "NOPE"), &String::from_str(&e, "Not a token"));
+    assert_eq!(r, Err(Ok(QuizError::InvalidToken)));
+
+    // decimals() above 18
+    let wide = e.register(WideDecimalsToken, ());
+    let r = c.try_add_approved_token(&wide, &String::from_str(&e, "WIDE"), &String::from_str(&e, "Wide decimals"));
+    assert_eq!(r, Err(Ok(QuizError::InvalidToken)));
+    assert!(!c.is_token_approved(&wide));
+}
+
+#[test]
+fn room_below_min_host_fee_is_rejected() {
+    let e = Env::default(); e.mock_all_auths();
+    let (c, _, _, _, tokens) = initialize_contract_with_tokens(&e);
+    let host = Address::generate(&e);
+    let t = tokens.get(0).unwrap();
+
+    assert_eq!(c.try_set_min_host_fee_bps(&600), Err(Ok(QuizError::InvalidHostFee)));
+    c.set_min_host_fee_bps(&100);
+
+    let r = c.try_init_pool_room(&1, &host, &t, &1_000_000, &Some(50), &2000, &100, &None, &None, &None);
+    assert_eq!(r, Err(Ok(QuizError::InvalidHostFee)));
+    let r = c.try_init_pool_room(&1, &host, &t, &1_000_000, &None, &2000, &100, &None, &None, &None);
+    assert_eq!(r, Err(Ok(QuizError::InvalidHostFee)));
+
+    c.init_pool_room(&1, &host, &t, &1_000_000, &Some(100), &2000, &100, &None, &None, &None);
+}
+
+#[test]
+fn room_age_grows_with_ledger() {
+    let e = Env::default(); e.mock_all_auths();
+    let (c, _, _, _, tokens) = initialize_contract_with_tokens(&e);
+    let host = Address::generate(&e);
+    let t = tokens.get(0).unwrap();
+
+    e.ledger().with_mut(|l| l.sequence_number = 1_000);
+    c.init_pool_room(&1, &host, &t, &1_000_000, &None, &2000, &100, &None, &None, &None);
+    assert_eq!(c.get_room_config(&1).unwrap().creation_ledger(), 1_000);
+    assert_eq!(c.get_room_age(&1), Some(0));
+
+    e.ledger().with_mut(|l| l.sequence_number += 25);
+    assert_eq!(c.get_room_age(&1), Some(25));
+    assert_eq!(c.get_room_age(&2), None);
+}
+
+#[test]
+fn players_claim_refunds_after_cancel() {
+    let e = Env::default(); e.mock_all_auths();
+    let (c, contract_addr, _, _, tokens) = initialize_contract_with_tokens(&e);
+    let host = Address::generate(&e);
+    let p1 = Address::generate(&e);
+    let p2 = Address::generate(&e);
+    let outsider = Address::generate(&e);
+    let t = tokens.get(0).unwrap();
+    let tc = TokenClient::new(&e, &t);
+
+    mint_tokens_for_users(&e, &t, &[p1.clone(), p2.clone()], 10_000_000);
+    c.init_pool_room(&1, &host, &t, &1_000_000, &None, &2000, &100, &None, &None, &None);
+    c.join_room(&1, &p1, &String::from_str(&e, "P1"), &500_000);
+    c.join_room(&1, &p2, &String::from_str(&e, "P2"), &0);
+
+    // Nothing is owed while the room is live
+    assert_eq!(c.try_claim_refund(&1, &p1), Err(Ok(QuizError::InsufficientBalance)));
+
+    c.cancel_room(&1);
+    let cfg = c.get_room_config(&1).unwrap();
+    assert!(cfg.cancelled() && cfg.ended());
+    assert_eq!(c.try_end_room(&1, &Some(p1.clone()), &None, &None), Err(Ok(QuizError::RoomAlreadyEnded)));
+
+    c.claim_refund(&1, &p1);
+    assert_eq!(tc.balance(&p1), 10_000_000);
+    assert_eq!(c.try_claim_refund(&1, &p1), Err(Ok(QuizError::InsufficientBalance)));
+    assert_eq!(c.try_claim_refund(&1, &outsider), Err(Ok(QuizError::InsufficientBalance)));
+
+    // p2 has not claimed yet; their stake is still held
+    assert_eq!(tc.balance(&contract_addr), 1_000_000);
+}
+
+#[test]
+fn unique_symbols_reject_second_usdc() {
+    let e = Env::default(); e.mock_all_auths();
+    let (c, _, admin, _, _) = initialize_contract_with_tokens(&e);
+    let bridged = create_token_contract(&e, &admin);
+    let other = create_token_contract(&e, &admin);
+
+    c.set_enforce_unique_symbols(&true);
+    let r = c.try_add_approved_token(&bridged, &String::from_str(&e, "USDC"), &String::from_str(&e, "Bridged USD Coin"));
+    assert_eq!(r, Err(Ok(QuizError::TokenAlreadyExists)));
+
+    c.set_enforce_unique_symbols(&false);
+    c.add_approved_token(&bridged, &String::from_str(&e, "USDC"), &String::from_str(&e, "Bridged USD Coin"));
+    assert!(c.is_token_approved(&bridged));
+
+    c.set_enforce_unique_symbols(&true);
+    c.add_approved_token(&other, &String::from_str(&e, "EURX"), &String::from_str(&e, "Euro X"));
+}
+
+#[test]
+fn screen_name_availability() {
+    let e = Env::default(); e.mock_all_auths();
+    let (c, _, _, _, tokens) = initialize_contract_with_tokens(&e);
+    let host = Address::generate(&e);
+    let p = Address::generate(&e);
+    let t = tokens.get(0).unwrap();
+
+    mint_tokens_for_users(&e, &t, &[p.clone()], 10_000_000);
+    c.init_pool_room(&1, &host, &t, &1_000_000, &None, &2000, &100, &None, &None, &None);
+    c.join_room(&1, &p, &String::from_str(&e, "Taken"), &0);
+
+    assert!(!c.is_screen_name_available(&1, &String::from_str(&e, "Taken")));
+    assert!(c.is_screen_name_available(&1, &String::from_str(&e, "Fresh")));
+    assert!(!c.is_screen_name_available(&1, &String::from_str(&e, "")));
+    assert!(!c.is_screen_name_available(&1, &String::from_str(&e, "ThisNameIsFarTooLongToUse")));
+    assert!(!c.is_screen_name_available(&2, &String::from_str(&e, "Fresh")));
+}
+
+#[test]
+fn reentrancy_guard_is_scoped_to_one_room() {
+    let e = Env::default(); e.mock_all_auths();
+    let (c, contract_addr, _, _, tokens) = initialize_contract_with_tokens(&e);
+    let host = Address::generate(&e);
+    let p = Address::generate(&e);
+    let t = tokens.get(0).unwrap();
+
+    mint_tokens_for_users(&e, &t, &[p.clone()], 10_000_000);
+    for room in 1..=3u32 {
+        c.init_pool_room(&room, &host, &t, &1_000_000, &None, &2000, &100, &None, &None, &None);
+        c.join_room(&room, &p, &String::from_str(&e, "P"), &0);
+    }
+
+    // Rooms settle one after another without tripping each other's guard
+    c.end_room(&1, &Some(p.clone()), &None, &None);
+    c.end_room(&2, &Some(p.clone()), &None, &None);
+
+    // Simulate a distribution still in flight for room 3
+    let mut id = [0u8; 32];
+    id[28..32].copy_from_slice(&3u32.to_be_bytes());
+    let guard_key = (Symbol::new(&e, "reentry"), BytesN::from_array(&e, &id));
+    e.as_contract(&contract_addr, || e.storage().instance().set(&guard_key, &true));
+
+    let r = c.try_end_room(&3, &Some(p.clone()), &None, &None);
+    assert_eq!(r, Err(Ok(QuizError::ReentrancyDetected)));
+}
+
+#[test]
+fn lowering_platform_fee_raises_charity_for_new_rooms() {
+    let e = Env::default(); e.mock_all_auths();
+    let (c, _, _, _, tokens) = initialize_contract_with_tokens(&e);
+    let host = Address::generate(&e);
+    let t = tokens.get(0).unwrap();
+
+    c.init_pool_room(&1, &host, &t, &1_000_000, &None, &2000, &100, &None, &None, &None);
+    assert_eq!(c.get_platform_fee_bps(), 2000);
+
+    // Platform plus the 50% charity minimum cannot exceed 100%
+    assert_eq!(c.try_set_platform_fee_bps(&5001), Err(Ok(QuizError::PercentageTooHigh)));
+
+    c.set_platform_fee_bps(&1500);
+    assert_eq!(c.get_platform_fee_bps(), 1500);
+    c.init_pool_room(&2, &host, &t, &1_000_000, &None, &2000, &100, &None, &None, &None);
+
+    assert_eq!(c.get_effective_splits(&1), Some((2000, 0, 2000, 6000)));
+    assert_eq!(c.get_effective_splits(&2), Some((1500, 0, 2000, 6500)));
+}
+
+#[test]
+fn asset_room_prize_count_must_match_winners() {
+    let e = Env::default(); e.mock_all_auths();
+    let (c, _, _, _, tokens) = initialize_contract_with_tokens(&e);
+    let host = Address::generate(&e);
+    let fee = tokens.get(0).unwrap();
+    let prize = tokens.get(1).unwrap();
+
+    mint_tokens_for_users(&e, &prize, &[host.clone()], 10_000_000);
+    let three = Vec::from_array(&e, [
+        PrizeAsset { contract_id: prize.clone(), amount: 3_000_000 },
+        PrizeAsset { contract_id: prize.clone(), amount: 2_000_000 },
+        PrizeAsset { contract_id: prize.clone(), amount: 1_000_000 },
+    ]);
+    let one = Vec::from_array(&e, [PrizeAsset { contract_id: prize.clone(), amount: 3_000_000 }]);
+
+    let r = c.try_init_asset_room(&1, &host, &fee, &1_000_000, &None, &three, &1);
+    assert_eq!(r, Err(Ok(QuizError::InvalidPrizeAssets)));
+    let r = c.try_init_asset_room(&1, &host, &fee, &1_000_000, &None, &one, &3);
+    assert_eq!(r, Err(Ok(QuizError::InvalidPrizeAssets)));
+
+    c.init_asset_room(&1, &host, &fee, &1_000_000, &None, &one, &1);
+    c.init_asset_room(&2, &host, &fee, &1_000_000, &None, &three, &3);
+}
+
+#[test]
+fn rooms_using_token_filters_by_token() {
+    let e = Env::default(); e.mock_all_auths();
+    let (c, _, _, _, tokens) = initialize_contract_with_tokens(&e);
+    let host = Address::generate(&e);
+    let p = Address::generate(&e);
+    let usdc = tokens.get(0).unwrap();
+    let xlm = tokens.get(1).unwrap();
+    let eurc = tokens.get(2).unwrap();
+
+    mint_tokens_for_users(&e, &usdc, &[p.clone()], 10_000_000);
+    mint_tokens_for_users(&e, &eurc, &[host.clone()], 10_000_000);
+
+    c.init_pool_room(&1, &host, &usdc, &1_000_000, &None, &2000, &100, &None, &None, &None);
+    c.init_pool_room(&2, &host, &xlm, &1_000_000, &None, &2000, &100, &None, &None, &None);
+    c.init_pool_room(&3, &host, &usdc, &1_000_000, &None, &2000, &100, &None, &None, &None);
+    let prizes = Vec::from_array(&e, [PrizeAsset { contract_id: eurc.clone(), amount: 1_000_000 }]);
+    c.init_asset_room(&4, &host, &xlm, &1_000_000, &None, &prizes, &1);
+
+    assert_eq!(c.get_rooms_using_token(&usdc), Vec::from_array(&e, [1, 3]));
+    assert_eq!(c.get_rooms_using_token(&xlm), Vec::from_array(&e, [2, 4]));
+    assert_eq!(c.get_rooms_using_token(&eurc), Vec::from_array(&e, [4]));
+
+    // Ended rooms no longer depend on the token
+    c.join_room(&1, &p, &String::from_str(&e, "P"), &0);
+    c.end_room(&1, &Some(p.clone()), &None, &None);
+    assert_eq!(c.get_rooms_using_token(&usdc), Vec::from_array(&e, [3]));
+}
+
+#[test]
+fn platform_stats_track_room_lifecycle() {
+    let e = Env::default(); e.mock_all_auths();
+    let (c, _, _, _, tokens) = initialize_contract_with_tokens(&e);
+    let host = Address::generate(&e);
+    let p1 = Address::generate(&e);
+    let p2 = Address::generate(&e);
+    let t = tokens.get(0).unwrap();
+
+    mint_tokens_for_users(&e, &t, &[p1.clone(), p2.clone()], 10_000_000);
+    assert_eq!(c.get_platform_stats(), (0, 0, 0, 0));
+
+    c.init_pool_room(&1, &host, &t, &1_000_000, &None, &2000, &100, &None, &None, &None);
+    c.init_pool_room(&2, &host, &t, &2_000_000, &None, &2000, &100, &None, &None, &None);
+    c.join_room(&1, &p1, &String::from_str(&e, "P1"), &0);
+    c.join_room(&1, &p2, &String::from_str(&e, "P2"), &0);
+    c.join_room(&2, &p1, &String::from_str(&e, "P1"), &0);
+    assert_eq!(c.get_platform_stats(), (2, 2, 0, 0));
+
+    c.end_room(&1, &Some(p1.clone()), &None, &None);
+    assert_eq!(c.get_platform_stats(), (2, 1, 1, 2_000_000));
+
+    // A failed end changes nothing; a cancel closes the room without adding volume
+    assert!(c.try_end_room(&1, &Some(p1.clone()), &None, &None).is_err());
+    c.cancel_room(&2);
+    assert_eq!(c.get_platform_stats(), (2, 0, 2, 2_000_000));
+}
+
+#[test]
+fn token_lifetime_rooms_outlive_the_rooms() {
+    let e = Env::default(); e.mock_all_auths();
+    let (c, _, _, _, tokens) = initialize_contract_with_tokens(&e);
+    let host = Address::generate(&e);
+    let p = Address::generate(&e);
+    let usdc = tokens.get(0).unwrap();
+    let xlm = tokens.get(1).unwrap();
+
+    mint_tokens_for_users(&e, &usdc, &[p.clone()], 10_000_000);
+    for room_id in 1..=2u32 {
+        c.init_pool_room(&room_id, &host, &usdc, &1_000_000, &None, &2000, &100, &None, &None, &None);
+        c.join_room(&room_id, &p, &String::from_str(&e, "P"), &0);
+        c.end_room(&room_id, &Some(p.clone()), &None, &None);
+    }
+
+    assert!(c.get_rooms_using_token(&usdc).is_empty());
+    assert_eq!(c.get_token_lifetime_rooms(&usdc), 2);
+    assert_eq!(c.get_token_lifetime_rooms(&xlm), 0);
+}
+
+#[test]
+fn cancelled_asset_room_restores_both_tokens() {
+    let e = Env::default(); e.mock_all_auths();
+    let (c, contract_addr, _, _, tokens) = initialize_contract_with_tokens(&e);
+    let host = Address::generate(&e);
+    let p1 = Address::generate(&e);
+    let fee = tokens.get(0).unwrap();
+    let prize = tokens.get(1).unwrap();
+    let fee_client = TokenClient::new(&e, &fee);
+    let prize_client = TokenClient::new(&e, &prize);
+
+    mint_tokens_for_users(&e, &prize, &[host.clone()], 5_000_000);
+    mint_tokens_for_users(&e, &fee, &[p1.clone()], 10_000_000);
+
+    let prizes = Vec::from_array(&e, [PrizeAsset { contract_id: prize.clone(), amount: 5_000_000 }]);
+    c.init_asset_room(&1, &host, &fee, &1_000_000, &None, &prizes, &1);
+    c.join_room(&1, &p1, &String::from_str(&e, "P1"), &250_000);
+    assert_eq!(c.get_room_status(&1), RoomStatus::Active);
+    assert_eq!(prize_client.balance(&host), 0);
+
+    c.cancel_room(&1);
+    assert_eq!(c.get_room_status(&1), RoomStatus::Cancelled);
+    assert_eq!(prize_client.balance(&host), 5_000_000);
+    assert_eq!(c.get_escrowed_amount(&prize), 0);
+
+    c.claim_refund(&1, &p1);
+    assert_eq!(fee_client.balance(&p1), 10_000_000);
+    assert_eq!(fee_client.balance(&contract_addr), 0);
+    assert_eq!(prize_client.balance(&contract_addr), 0);
+}
+
+#[test]
+fn end_room_notifies_callback_contract() {
+    let e = Env::default(); e.mock_all_auths();
+    let (c, _, _, _, tokens) = initialize_contract_with_tokens(&e);
+    let host = Address::generate(&e);
+    let p1 = Address::generate(&e);
+    let t = tokens.get(0).unwrap();
+    let recorder = e.register(RoomEndedRecorder, ());
+    let recorder_client = RoomEndedRecorderClient::new(&e, &recorder);
+
+    mint_tokens_for_users(&e, &t, &[p1.clone()], 10_000_000);
+
+    let options = RoomOptions { callback_contract: Some(recorder.clone()), ..Default::default() };
+    c.init_pool_room(&1, &host, &t, &1_000_000, &None, &2000, &100, &None, &None, &Some(options));
+    c.join_room(&1, &p1, &String::from_str(&e, "P1"), &0);
+    c.end_room(&1, &Some(p1.clone()), &None, &None);
+
+    assert_eq!(recorder_client.last(), Some((1, Vec::from_array(&e, [p1.clone()]))));
+
+    // A callback target without on_room_ended doesn't block the payout
+    let broken = RoomOptions { callback_contract: Some(t.clone()), ..Default::default() };
+    c.init_pool_room(&2, &host, &t, &1_000_000, &None, &2000, &100, &None, &None, &Some(broken));
+    c.join_room(&2, &p1, &String::from_str(&e, "P1"), &0);
+    c.end_room(&2, &Some(p1.clone()), &None, &None);
+    assert_eq!(c.get_room_status(&2), RoomStatus::Ended);
+}
+
+#[test]
+fn pool_cap_rejects_joins_past_limit() {
+    let e = Env::default(); e.mock_all_auths();
+    let (c, _, _, _, tokens) = initialize_contract_with_tokens(&e);
+    let host = Address::generate(&e);
+    let p1 = Address::generate(&e);
+    let p2 = Address::generate(&e);
+    let p3 = Address::generate(&e);
+    let t = tokens.get(0).unwrap();
+
+    mint_tokens_for_users(&e, &t, &[p1.clone(), p2.clone(), p3.clone()], 10_000_000);
+
+    // Cap must fit at least one entry
+    let too_small = RoomOptions { max_total_pool: Some(500_000), ..Default::default() };
+    let r = c.try_init_pool_room(&1, &host, &t, &1_000_000, &None, &2000, &100, &None, &None, &Some(too_small));
+    assert_eq!(r, Err(Ok(QuizError::InsufficientAmount)));
+
+    let capped = RoomOptions { max_total_pool: Some(2_500_000), ..Default::default() };
+    c.init_pool_room(&1, &host, &t, &1_000_000, &None, &2000, &100, &None, &None, &Some(capped));
+    c.join_room(&1, &p1, &String::from_str(&e, "P1"), &500_000);
+    c.join_room(&1, &p2, &String::from_str(&e, "P2"), &0);
+    assert_eq!(c.get_room_config(&1).unwrap().total_pool(), 2_500_000);
+
+    let r = c.try_join_room(&1, &p3, &String::from_str(&e, "P3"), &0);
+    assert_eq!(r, Err(Ok(QuizError::AmountTooLarge)));
+    assert_eq!(TokenClient::new(&e, &t).balance(&p3), 10_000_000);
+}
+
+#[test]
+fn asset_distribution_preview_matches_payout() {
+    let e = Env::default(); e.mock_all_auths();
+    let (c, _, _, platform, tokens) = initialize_contract_with_tokens(&e);
+    let charity = c.get_charity_wallet();
+    let host = Address::generate(&e);
+    let p1 = Address::generate(&e);
+    let p2 = Address::generate(&e);
+    let outsider = Address::generate(&e);
+    let fee = tokens.get(0).unwrap();
+    let gold = tokens.get(1).unwrap();
+    let silver = tokens.get(2).unwrap();
+
+    mint_tokens_for_users(&e, &gold, &[host.clone()], 5_000_000);
+    mint_tokens_for_users(&e, &silver, &[host.clone()], 2_000_000);
+    mint_tokens_for_users(&e, &fee, &[p1.clone(), p2.clone()], 10_000_000);
+
+    let prizes = Vec::from_array(&e, [
+        PrizeAsset { contract_id: gold.clone(), amount: 5_000_000 },
+        PrizeAsset { contract_id: silver.clone(), amount: 2_000_000 },
+    ]);
+    c.init_asset_room(&1, &host, &fee, &1_000_000, &Some(500), &prizes, &2);
+    c.join_room(&1, &p1, &String::from_str(&e, "P1"), &0);
+    c.join_room(&1, &p2, &String::from_str(&e, "P2"), &0);
+
+    let r = c.try_preview_asset_distribution(&1, &Vec::from_array(&e, [outsider.clone()]));
+    assert_eq!(r, Err(Ok(QuizError::InvalidWinners)));
+
+    let plan = c.preview_asset_distribution(&1, &Vec::from_array(&e, [p2.clone(), p1.clone()]));
+    assert_eq!(plan.get(0).unwrap(), (p2.clone(), gold.clone(), 5_000_000));
+    assert_eq!(plan.get(1).unwrap(), (p1.clone(), silver.clone(), 2_000_000));
+    let fee_total: i128 = plan.iter().filter(|(_, token, _)| *token == fee).map(|(_, _, amount)| amount).sum();
+    assert_eq!(fee_total, 2_000_000);
+
+    c.end_room(&1, &Some(p2.clone()), &Some(p1.clone()), &None);
+
+    // Every recipient in the plan started with none of the token they're listed for
+    for (recipient, token, amount) in plan.iter() {
+        assert_eq!(TokenClient::new(&e, &token).balance(&recipient), amount);
+    }
+    assert!(plan.iter().any(|(r, _, _)| r == platform));
+    assert!(plan.iter().any(|(r, _, _)| r == charity));
+    assert!(plan.iter().any(|(r, _, _)| r == host));
+}
+
+#[test]
+fn pause_lapses_after_auto_unpause_ledger() {
+    let e = Env::default(); e.mock_all_auths();
+    let (c, _, _, _, tokens) = initialize_contract_with_tokens(&e);
+    let host = Address::generate(&e);
+    let p = Address::generate(&e);
+    let t = tokens.get(0).unwrap();
+
+    mint_tokens_for_users(&e, &t, &[p.clone()], 2_000_000);
+    c.init_pool_room(&1, &host, &t, &1_000_000, &None, &2000, &100, &None, &None, &None);
+
+    e.ledger().with_mut(|l| l.sequence_number = 100);
+    c.emergency_pause(&None, &Some(150));
+
+    e.ledger().with_mut(|l| l.sequence_number = 150);
+    assert!(c.is_emergency_paused());
+    let r = c.try_join_room(&1, &p, &String::from_str(&e, "P"), &0);
+    assert_eq!(r, Err(Ok(QuizError::EmergencyPause)));
+
+    // No unpause call: the pause simply expires
+    e.ledger().with_mut(|l| l.sequence_number = 151);
+    assert!(!c.is_emergency_paused());
+    assert_eq!(c.get_pause_info(), None);
+    c.join_room(&1, &p, &String::from_str(&e, "P"), &0);
+}
+
+#[test]
+fn player_outcome_reports_paid_and_received() {
+    let e = Env::default(); e.mock_all_auths();
+    let (c, _, _, _, tokens) = initialize_contract_with_tokens(&e);
+    let host = Address::generate(&e);
+    let p1 = Address::generate(&e);
+    let p2 = Address::generate(&e);
+    let p3 = Address::generate(&e);
+    let outsider = Address::generate(&e);
+    let t = tokens.get(0).unwrap();
+    let tc = TokenClient::new(&e, &t);
+
+    mint_tokens_for_users(&e, &t, &[p1.clone(), p2.clone(), p3.clone()], 10_000_000);
+    c.init_pool_room(&1, &host, &t, &1_000_000, &None, &2500, &100, &None, &None, &None);
+    c.join_room(&1, &p1, &String::from_str(&e, "P1"), &0);
+    c.join_room(&1, &p2, &String::from_str(&e, "P2"), &0);
+    c.join_room(&1, &p3, &String::from_str(&e, "P3"), &1_500_000);
+
+    assert_eq!(c.get_player_outcome(&1, &p1), Some((1_000_000, 0, -1_000_000)));
+    c.end_room(&1, &Some(p1.clone()), &None, &None);
+
+    let received = tc.balance(&p1) - 9_000_000;
+    assert!(received > 1_000_000);
+    assert_eq!(c.get_player_outcome(&1, &p1), Some((1_000_000, received, received - 1_000_000)));
+    assert_eq!(c.get_player_outcome(&1, &p3), Some((2_500_000, 0, -2_500_000)));
+    assert_eq!(c.get_player_outcome(&1, &outsider), None);
+}
+
+#[test]
+fn mapped_asset_end_gives_grand_prize_to_any_player() {
+    let e = Env::default(); e.mock_all_auths();
+    let (c, _, _, _, tokens) = initialize_contract_with_tokens(&e);
+    let host = Address::generate(&e);
+    let p1 = Address::generate(&e);
+    let p2 = Address::generate(&e);
+    let outsider = Address::generate(&e);
+    let fee = tokens.get(0).unwrap();
+    let gold = tokens.get(1).unwrap();
+    let silver = tokens.get(2).unwrap();
+
+    mint_tokens_for_users(&e, &gold, &[host.clone()], 5_000_000);
+    mint_tokens_for_users(&e, &silver, &[host.clone()], 2_000_000);
+    mint_tokens_for_users(&e, &fee, &[p1.clone(), p2.clone()], 10_000_000);
+
+    let prizes = Vec::from_array(&e, [
+        PrizeAsset { contract_id: gold.clone(), amount: 5_000_000 },
+        PrizeAsset { contract_id: silver.clone(), amount: 2_000_000 },
+    ]);
+    c.init_asset_room(&1, &host, &fee, &1_000_000, &None, &prizes, &2);
+    c.join_room(&1, &p1, &String::from_str(&e, "P1"), &0);
+    c.join_room(&1, &p2, &String::from_str(&e, "P2"), &0);
+
+    let duplicate = Vec::from_array(&e, [(p1.clone(), 0u32), (p2.clone(), 0u32)]);
+    assert_eq!(c.try_end_asset_room_mapped(&1, &duplicate), Err(Ok(QuizError::InvalidWinners)));
+    let stranger = Vec::from_array(&e, [(outsider.clone(), 0u32)]);
+    assert_eq!(c.try_end_asset_room_mapped(&1, &stranger), Err(Ok(QuizError::InvalidWinners)));
+
+    // The second player to join takes the grand prize
+    c.end_asset_room_mapped(&1, &Vec::from_array(&e, [(p1.clone(), 1u32), (p2.clone(), 0u32)]));
+    assert_eq!(TokenClient::new(&e, &gold).balance(&p2), 5_000_000);
+    assert_eq!(TokenClient::new(&e, &silver).balance(&p1), 2_000_000);
+    assert_eq!(c.get_room_config(&1).unwrap().winners().get(0), Some(p2.clone()));
+}
+
+#[test]
+fn join_delay_blocks_early_joins() {
+    let e = Env::default(); e.mock_all_auths();
+    let (c, _, _, _, tokens) = initialize_contract_with_tokens(&e);
+    let host = Address::generate(&e);
+    let p = Address::generate(&e);
+    let t = tokens.get(0).unwrap();
+
+    mint_tokens_for_users(&e, &t, &[p.clone()], 2_000_000);
+    e.ledger().with_mut(|l| l.sequence_number = 100);
+
+    let too_long = RoomOptions { join_delay_ledgers: Some(20_000), ..Default::default() };
+    let r = c.try_init_pool_room(&1, &host, &t, &1_000_000, &None, &2000, &100, &None, &None, &Some(too_long));
+    assert_eq!(r, Err(Ok(QuizError::InvalidJoinWindow)));
+
+    let delayed = RoomOptions { join_delay_ledgers: Some(10), ..Default::default() };
+    c.init_pool_room(&1, &host, &t, &1_000_000, &None, &2000, &100, &None, &None, &Some(delayed));
+
+    let r = c.try_join_room(&1, &p, &String::from_str(&e, "P"), &0);
+    assert_eq!(r, Err(Ok(QuizError::JoinWindowClosed)));
+
+    e.ledger().with_mut(|l| l.sequence_number = 110);
+    c.join_room(&1, &p, &String::from_str(&e, "P"), &0);
+}
+
+#[test]
+fn admin_clears_wedged_reentrancy_guard() {
+    let e = Env::default(); e.mock_all_auths();
+    let (c, contract_addr, _, _, tokens) = initialize_contract_with_tokens(&e);
+    let host = Address::generate(&e);
+    let p = Address::generate(&e);
+    let t = tokens.get(0).unwrap();
+
+    mint_tokens_for_users(&e, &t, &[p.clone()], 10_000_000);
+    c.init_pool_room(&1, &host, &t, &1_000_000, &None, &2000, &100, &None, &None, &None);
+    c.join_room(&1, &p, &String::from_str(&e, "P"), &0);
+    assert!(!c.is_reentrancy_locked(&1));
+
+    // Guard left behind by a payout that never cleared it
+    let mut id = [0u8; 32];
+    id[28..32].copy_from_slice(&1u32.to_be_bytes());
+    let guard_key = (Symbol::new(&e, "reentry"), BytesN::from_array(&e, &id));
+    e.as_contract(&contract_addr, || e.storage().instance().set(&guard_key, &true));
+
+    assert!(c.is_reentrancy_locked(&1));
+    assert_eq!(c.try_end_room(&1, &Some(p.clone()), &None, &None), Err(Ok(QuizError::ReentrancyDetected)));
+
+    c.clear_reentrancy_guard_admin(&1);
+    assert!(!c.is_reentrancy_locked(&1));
+    c.end_room(&1, &Some(p.clone()), &None, &None);
+}
+
+// Ends a three-winner room on a pool that doesn't divide evenly; returns (winners' total, receipt)
+fn settle_uneven_room(favor_players: bool) -> (i128, DistributionReceipt) {
+    let e = Env::default(); e.mock_all_auths();
+    let (c, _, _, _, tokens) = initialize_contract_with_tokens(&e);
+    let host = Address::generate(&e);
+    let p1 = Address::generate(&e);
+    let p2 = Address::generate(&e);
+    let p3 = Address::generate(&e);
+    let t = tokens.get(0).unwrap();
+    let tc = TokenClient::new(&e, &t);
+
+    mint_tokens_for_users(&e, &t, &[p1.clone(), p2.clone(), p3.clone()], 10_000_000);
+
+    let options = RoomOptions { favor_players, ..Default::default() };
+    c.init_pool_room(&1, &host, &t, &1_000_001, &None, &2000, &60, &Some(30), &Some(10), &Some(options));
+    c.join_room(&1, &p1, &String::from_str(&e, "P1"), &0);
+    c.join_room(&1, &p2, &String::from_str(&e, "P2"), &0);
+    c.join_room(&1, &p3, &String::from_str(&e, "P3"), &0);
+    c.end_room(&1, &Some(p1.clone()), &Some(p2.clone()), &Some(p3.clone()));
+
+    let won = tc.balance(&p1) + tc.balance(&p2) + tc.balance(&p3) - 3 * (10_000_000 - 1_000_001);
+    (won, c.get_distribution_receipt(&1).unwrap())
+}
+
+#[test]
+fn favor_players_keeps_rounding_dust_with_winners() {
+    let (plain_won, plain) = settle_uneven_room(false);
+    let (favor_won, favor) = settle_uneven_room(true);
+
+    // The plain room sweeps truncated sub-units to charity; the favoured one pays them to winners
+    assert!(favor_won > plain_won);
+    assert!(plain.remainder > 0);
+    assert_eq!(favor.remainder, 0);
+    assert_eq!(favor.prize, favor_won);
+}
+
+#[test]
+fn drained_contract_fails_before_any_payout() {
+    let e = Env::default(); e.mock_all_auths();
+    let (c, contract_addr, _, platform, tokens) = initialize_contract_with_tokens(&e);
+    let host = Address::generate(&e);
+    let p1 = Address::generate(&e);
+    let p2 = Address::generate(&e);
+    let thief = Address::generate(&e);
+    let t = tokens.get(0).unwrap();
+    let tc = TokenClient::new(&e, &t);
+
+    mint_tokens_for_users(&e, &t, &[p1.clone(), p2.clone()], 10_000_000);
+    c.init_pool_room(&1, &host, &t, &1_000_000, &None, &2000, &100, &None, &None, &None);
+    c.join_room(&1, &p1, &String::from_str(&e, "P1"), &0);
+    c.join_room(&1, &p2, &String::from_str(&e, "P2"), &0);
+
+    // Simulate a sweep that left the contract short of the room's pool
+    tc.transfer(&contract_addr, &thief, &1);
+
+    let r = c.try_end_room(&1, &Some(p1.clone()), &None, &None);
+    assert_eq!(r, Err(Ok(QuizError::InsufficientBalance)));
+    assert_eq!(tc.balance(&platform), 0);
+    assert_eq!(tc.balance(&p1), 9_000_000);
+    assert!(!c.get_room_config(&1).unwrap().ended());
+}
+
+#[test]
+fn host_fee_update_moves_charity_until_first_join() {
+    let e = Env::default(); e.mock_all_auths();
+    let (c, _, _, _, tokens) = initialize_contract_with_tokens(&e);
+    let host = Address::generate(&e);
+    let p = Address::generate(&e);
+    let t = tokens.get(0).unwrap();
+
+    mint_tokens_for_users(&e, &t, &[p.clone()], 2_000_000);
+    c.init_pool_room(&1, &host, &t, &1_000_000, &Some(100), &2000, &100, &None, &None, &None);
+    assert_eq!(c.get_effective_splits(&1), Some((2000, 100, 2000, 5900)));
+
+    assert_eq!(c.try_update_host_fee(&1, &600), Err(Ok(QuizError::InvalidHostFee)));
+
+    c.update_host_fee(&1, &400);
+    assert_eq!(c.get_effective_splits(&1), Some((2000, 400, 2000, 5600)));
+
+    c.join_room(&1, &p, &String::from_str(&e, "P"), &0);
+    assert_eq!(c.try_update_host_fee(&1, &0), Err(Ok(QuizError::PlayerAlreadyJoined)));
+}
+
+#[test]
+fn is_initialized_flips_after_initialize() {
+    let e = Env::default(); e.mock_all_auths();
+    let (contract, _) = create_quiz_contract(&e);
+    assert!(!contract.is_initialized());
+
+    contract.initialize(&Address::generate(&e), &Address::generate(&e), &Address::generate(&e));
+    assert!(contract.is_initialized());
+}
+
+#[test]
+fn extras_ratio_caps_extras_per_entry() {
+    let e = Env::default(); e.mock_all_auths();
+    let (c, _, _, _, tokens) = initialize_contract_with_tokens(&e);
+    let host = Address::generate(&e);
+    let p1 = Address::generate(&e);
+    let p2 = Address::generate(&e);
+    let t = tokens.get(0).unwrap();
+
+    mint_tokens_for_users(&e, &t, &[p1.clone(), p2.clone()], 10_000_000);
+
+    let zero = RoomOptions { max_extras_ratio_bps: Some(0), ..Default::default() };
+    let r = c.try_init_pool_room(&1, &host, &t, &1_000_000, &None, &2000, &100, &None, &None, &Some(zero));
+    assert_eq!(r, Err(Ok(QuizError::InvalidExtrasIncrement)));
+
+    // Extras may be at most half the entry fee
+    let capped = RoomOptions { max_extras_ratio_bps: Some(5000), ..Default::default() };
+    c.init_pool_room(&1, &host, &t, &1_000_000, &None, &2000, &100, &None, &None, &Some(capped));
+
+    let r = c.try_join_room(&1, &p1, &String::from_str(&e, "P1"), &500_001);
+    assert_eq!(r, Err(Ok(QuizError::InvalidExtrasIncrement)));
+
+    c.join_room(&1, &p1, &String::from_str(&e, "P1"), &500_000);
+    c.join_room(&1, &p2, &String::from_str(&e, "P2"), &0);
+}
+
+#[test]
+fn prize_pool_amount_tracks_pool_and_escrow() {
+    let e = Env::default(); e.mock_all_auths();
+    let (c, _, _, _, tokens) = initialize_contract_with_tokens(&e);
+    let host = Address::generate(&e);
+    let p1 = Address::generate(&e);
+    let p2 = Address::generate(&e);
+    let fee = tokens.get(0).unwrap();
+    let prize = tokens.get(1).unwrap();
+
+    mint_tokens_for_users(&e, &fee, &[p1.clone(), p2.clone()], 10_000_000);
+    mint_tokens_for_users(&e, &prize, &[host.clone()], 5_000_000);
+
+    c.init_pool_room(&1, &host, &fee, &1_000_000, &Some(300), &2000, &100, &None, &None, &None);
+    assert_eq!(c.get_prize_pool_amount(&1), Some(0));
+    c.join_room(&1, &p1, &String::from_str(&e, "P1"), &500_000);
+    c.join_room(&1, &p2, &String::from_str(&e, "P2"), &0);
+
+    // 20% of the 2_500_000 pool
+    assert_eq!(c.get_prize_pool_amount(&1), Some(500_000));
+
+    let prizes = Vec::from_array(&e, [PrizeAsset { contract_id: prize.clone(), amount: 5_000_000 }]);
+    c.init_asset_room(&2, &host, &fee, &1_000_000, &None, &prizes, &1);
+    assert_eq!(c.get_prize_pool_amount(&2), Some(5_000_000));
+    assert_eq!(c.get_prize_pool_amount(&3), None);
+}
+
+#[test]
+fn total_room_cap_blocks_creation_at_limit() {
+    let e = Env::default(); e.mock_all_auths();
+    let (c, _, _, _, tokens) = initialize_contract_with_tokens(&e);
+    let host_a = Address::generate(&e);
+    let host_b = Address::generate(&e);
+    let t = tokens.get(0).unwrap();
+    let prize = tokens.get(1).unwrap();
+
+    mint_tokens_for_users(&e, &prize, &[host_b.clone()], 5_000_000);
+    c.set_max_total_rooms(&2);
+
+    c.init_pool_room(&1, &host_a, &t, &1_000_000, &None, &2000, &100, &None, &None, &None);
+    c.init_pool_room(&2, &host_b, &t, &1_000_000, &None, &2000, &100, &None, &None, &None);
+
+    let r = c.try_init_pool_room(&3, &host_a, &t, &1_000_000, &None, &2000, &100, &None, &None, &None);
+    assert_eq!(r, Err(Ok(QuizError::HostRoomLimitReached)));
+    let prizes = Vec::from_array(&e, [PrizeAsset { contract_id: prize.clone(), amount: 5_000_000 }]);
+    let r = c.try_init_asset_room(&3, &host_b, &t, &1_000_000, &None, &prizes, &1);
+    assert_eq!(r, Err(Ok(QuizError::HostRoomLimitReached)));
+
+    // Closing a room frees its slot
+    c.cancel_room(&2);
+    c.init_pool_room(&3, &host_a, &t, &1_000_000, &None, &2000, &100, &None, &None, &None);
+
+    c.set_max_total_rooms(&0);
+    c.init_pool_room(&4, &host_a, &t, &1_000_000, &None, &2000, &100, &None, &None, &None);
+}
+
+#[test]
+fn ending_a_room_frees_a_total_rooms_slot() {
+    let e = Env::default(); e.mock_all_auths();
+    let (c, _, _, _, tokens) = initialize_contract_with_tokens(&e);
+    let host = Address::generate(&e);
+    let p = Address::generate(&e);
+    let t = tokens.get(0).unwrap();
+
+    mint_tokens_for_users(&e, &t, &[p.clone()], 10_000_000);
+    c.set_max_total_rooms(&1);
+    c.init_pool_room(&1, &host, &t, &1_000_000, &None, &2000, &100, &None, &None, &None);
+    let r = c.try_init_pool_room(&2, &host, &t, &1_000_000, &None, &2000, &100, &None, &None, &None);
+    assert_eq!(r, Err(Ok(QuizError::HostRoomLimitReached)));
+
+    c.join_room(&1, &p, &String::from_str(&e, "P"), &0);
+    c.end_room(&1, &Some(p.clone()), &None, &None);
+    c.init_pool_room(&2, &host, &t, &1_000_000, &None, &2000, &100, &None, &None, &None);
+}
+
+#[test]
+fn normally_built_room_is_consistent() {
+    let e = Env::default(); e.mock_all_auths();
+    let (c, _, _, _, tokens) = initialize_contract_with_tokens(&e);
+    let host = Address::generate(&e);
+    let p1 = Address::generate(&e);
+    let p2 = Address::generate(&e);
+    let t = tokens.get(0).unwrap();
+
+    mint_tokens_for_users(&e, &t, &[p1.clone(), p2.clone()], 10_000_000);
+    c.init_pool_room(&1, &host, &t, &1_000_000, &None, &2000, &100, &None, &None, &None);
+    assert!(c.verify_room_consistency(&1));
+
+    c.join_room(&1, &p1, &String::from_str(&e, "P1"), &250_000);
+    c.join_room(&1, &p2, &String::from_str(&e, "P2"), &0);
+    assert!(c.verify_room_consistency(&1));
+
+    c.end_room(&1, &Some(p1.clone()), &None, &None);
+    assert!(c.verify_room_consistency(&1));
+    assert!(!c.verify_room_consistency(&2));
+}
+
+#[test]
+fn failed_second_escrow_reverts_the_whole_creation() {
+    let e = Env::default(); e.mock_all_auths();
+    let (c, contract_addr, _, _, tokens) = initialize_contract_with_tokens(&e);
+    let host = Address::generate(&e);
+    let fee = tokens.get(0).unwrap();
+    let gold = tokens.get(1).unwrap();
+    let silver = tokens.get(2).unwrap();
+
+    // Enough gold for first place but not enough silver for second
+    mint_tokens_for_users(&e, &gold, &[host.clone()], 5_000_000);
+    mint_tokens_for_users(&e, &silver, &[host.clone()], 1_000_000);
+
+    let prizes = Vec::from_array(&e, [
+        PrizeAsset { contract_id: gold.clone(), amount: 5_000_000 },
+        PrizeAsset { contract_id: silver.clone(), amount: 2_000_000 },
+    ]);
+    let r = c.try_init_asset_room(&1, &host, &fee, &1_000_000, &None, &prizes, &2);
+    assert_eq!(r, Err(Ok(QuizError::InsufficientBalance)));
+
+    // The gold transfer already ran inside the call; the host-level rollback undoes it
+    assert_eq!(TokenClient::new(&e, &gold).balance(&host), 5_000_000);
+    assert_eq!(TokenClient::new(&e, &gold).balance(&contract_addr), 0);
+    assert_eq!(c.get_escrowed_amount(&gold), 0);
+    assert!(c.get_room_config(&1).is_none());
+    assert_eq!(c.get_platform_stats(), (0, 0, 0, 0));
+}
+
+#[test]
+fn refunds_work_while_paused() {
+    let e = Env::default(); e.mock_all_auths();
+    let (c, _, _, _, tokens) = initialize_contract_with_tokens(&e);
+    let host = Address::generate(&e);
+    let p1 = Address::generate(&e);
+    let p2 = Address::generate(&e);
+    let t = tokens.get(0).unwrap();
+
+    mint_tokens_for_users(&e, &t, &[p1.clone(), p2.clone()], 10_000_000);
+    c.init_pool_room(&1, &host, &t, &1_000_000, &None, &2000, &100, &None, &None, &None);
+    c.join_room(&1, &p1, &String::from_str(&e, "P1"), &0);
+
+    c.emergency_pause(&None, &None);
+    assert_eq!(c.try_join_room(&1, &p2, &String::from_str(&e, "P2"), &0), Err(Ok(QuizError::EmergencyPause)));
+    assert_eq!(c.try_end_room(&1, &Some(p1.clone()), &None, &None), Err(Ok(QuizError::EmergencyPause)));
+
+    c.cancel_room(&1);
+    c.claim_refund(&1, &p1);
+    assert_eq!(TokenClient::new(&e, &t).balance(&p1), 10_000_000);
+}
+
+#[test]
+fn lifetime_platform_fees_accumulate_across_rooms() {
+    let e = Env::default(); e.mock_all_auths();
+    let (c, _, _, platform, tokens) = initialize_contract_with_tokens(&e);
+    let host = Address::generate(&e);
+    let p = Address::generate(&e);
+    let t = tokens.get(0).unwrap();
+    let other = tokens.get(1).unwrap();
+
+    mint_tokens_for_users(&e, &t, &[p.clone()], 10_000_000);
+    c.init_pool_room(&1, &host, &t, &1_000_000, &None, &2000, &100, &None, &None, &None);
+    c.init_pool_room(&2, &host, &t, &2_000_000, &None, &2000, &100, &None, &None, &None);
+    c.join_room(&1, &p, &String::from_str(&e, "P"), &0);
+    c.join_room(&2, &p, &String::from_str(&e, "P"), &0);
+
+    c.end_room(&1, &Some(p.clone()), &None, &None);
+    assert_eq!(c.get_lifetime_platform_fees(&t), 200_000);
+    c.end_room(&2, &Some(p.clone()), &None, &None);
+
+    // 20% of 1_000_000 plus 20% of 2_000_000
+    assert_eq!(c.get_lifetime_platform_fees(&t), 600_000);
+    assert_eq!(TokenClient::new(&e, &t).balance(&platform), 600_000);
+    assert_eq!(c.get_lifetime_platform_fees(&other), 0);
+}
+
+#[test]
+fn winners_with_amounts_match_distribution() {
+    let e = Env::default(); e.mock_all_auths();
+    let (c, _, _, _, tokens) = initialize_contract_with_tokens(&e);
+    let host = Address::generate(&e);
+    let p1 = Address::generate(&e);
+    let p2 = Address::generate(&e);
+    let p3 = Address::generate(&e);
+    let t = tokens.get(0).unwrap();
+    let tc = TokenClient::new(&e, &t);
+
+    mint_tokens_for_users(&e, &t, &[p1.clone(), p2.clone(), p3.clone()], 10_000_000);
+    c.init_pool_room(&1, &host, &t, &1_000_000, &None, &2000, &60, &Some(30), &Some(10), &None);
+    c.join_room(&1, &p1, &String::from_str(&e, "P1"), &0);
+    c.join_room(&1, &p2, &String::from_str(&e, "P2"), &0);
+    c.join_room(&1, &p3, &String::from_str(&e, "P3"), &0);
+    assert_eq!(c.get_winners_with_amounts(&1).len(), 0);
+
+    c.end_room(&1, &Some(p2.clone()), &Some(p3.clone()), &Some(p1.clone()));
+
+    // 20% of 3_000_000 split 60/30/10
+    let expected = Vec::from_array(&e, [(p2.clone(), 360_000i128), (p3.clone(), 180_000i128), (p1.clone(), 60_000i128)]);
+    assert_eq!(c.get_winners_with_amounts(&1), expected);
+    for (winner, amount) in expected.iter() {
+        assert_eq!(tc.balance(&winner), 9_000_000 + amount);
+    }
+}
+
+#[test]
+fn players_pay_entry_in_different_accepted_tokens() {
+    let e = Env::default(); e.mock_all_auths();
+    let (c, contract_addr, _, platform, tokens) = initialize_contract_with_tokens(&e);
+    let host = Address::generate(&e);
+    let p1 = Address::generate(&e);
+    let p2 = Address::generate(&e);
+    let usdc = tokens.get(0).unwrap();
+    let xlm = tokens.get(1).unwrap();
+    let unaccepted = tokens.get(2).unwrap();
+    let usdc_client = TokenClient::new(&e, &usdc);
+    let xlm_client = TokenClient::new(&e, &xlm);
+
+    mint_tokens_for_users(&e, &usdc, &[p1.clone()], 10_000_000);
+    mint_tokens_for_users(&e, &xlm, &[p2.clone()], 50_000_000);
+
+    // XLM entries cost 5x the USDC entry
+    let mut accepted = Map::new(&e);
+    accepted.set(xlm.clone(), 5_000_000i128);
+    let options = RoomOptions { accepted_tokens: Some(accepted), ..Default::default() };
+    c.init_pool_room(&1, &host, &usdc, &1_000_000, &None, &2000, &100, &None, &None, &Some(options));
+
+    let r = c.try_join_room_with_token(&1, &p2, &String::from_str(&e, "P2"), &0, &unaccepted);
+    assert_eq!(r, Err(Ok(QuizError::InvalidFeeToken)));
+
+    c.join_room(&1, &p1, &String::from_str(&e, "P1"), &0);
+    c.join_room_with_token(&1, &p2, &String::from_str(&e, "P2"), &0, &xlm);
+
+    let cfg = c.get_room_config(&1).unwrap();
+    assert_eq!(cfg.total_pool(), 1_000_000);
+    assert_eq!(cfg.token_pool(&xlm), 5_000_000);
+    assert_eq!(xlm_client.balance(&contract_addr), 5_000_000);
+
+    c.end_room(&1, &Some(p2.clone()), &None, &None);
+
+    // Each sub-pool is split on its own: 20% platform, 20% prize
+    assert_eq!(usdc_client.balance(&platform), 200_000);
+    assert_eq!(xlm_client.balance(&platform), 1_000_000);
+    assert_eq!(usdc_client.balance(&p2), 200_000);
+    assert_eq!(xlm_client.balance(&p2), 45_000_000 + 1_000_000);
+    assert_eq!(usdc_client.balance(&contract_addr), 0);
+    assert_eq!(xlm_client.balance(&contract_addr), 0);
+
+    // Refunds go back in the token the entry was paid with
+    let options = RoomOptions { accepted_tokens: cfg.accepted_tokens().clone(), ..Default::default() };
+    c.init_pool_room(&2, &host, &usdc, &1_000_000, &None, &2000, &100, &None, &None, &Some(options));
+    c.join_room_with_token(&2, &p2, &String::from_str(&e, "P2"), &0, &xlm);
+    c.cancel_room(&2);
+    c.claim_refund(&2, &p2);
+    assert_eq!(xlm_client.balance(&p2), 46_000_000);
+}
+
+#[test]
+fn room_header_tracks_joins_and_end() {
+    let e = Env::default(); e.mock_all_auths();
+    let (c, _, _, _, tokens) = initialize_contract_with_tokens(&e);
+    let host = Address::generate(&e);
+    let p1 = Address::generate(&e);
+    let p2 = Address::generate(&e);
+    let t = tokens.get(0).unwrap();
+
+    mint_tokens_for_users(&e, &t, &[p1.clone(), p2.clone()], 10_000_000);
+    c.init_pool_room(&1, &host, &t, &1_000_000, &None, &2000, &100, &None, &None, &None);
+    assert_eq!(c.get_room_header(&1).unwrap().player_count, 0);
+
+    c.join_room(&1, &p1, &String::from_str(&e, "P1"), &0);
+    c.join_room(&1, &p2, &String::from_str(&e, "P2"), &500_000);
+    assert_eq!(c.get_room_header(&1), Some(RoomHeader {
+        room_id: 1,
+        host: host.clone(),
+        fee_token: t.clone(),
+        status: RoomStatus::Active,
+        player_count: 2,
+        total_pool: 2_500_000,
+        prize_mode: PrizeMode::PrizePoolSplit,
+        tokens: Vec::from_array(&e, [t.clone()]),
+    }));
+
+    // A failed update leaves the header untouched
+    let _ = c.try_join_room(&1, &p1, &String::from_str(&e, "P1 again"), &0);
+    assert_eq!(c.get_room_header(&1).unwrap().player_count, 2);
+
+    c.end_room(&1, &Some(p1.clone()), &None, &None);
+    assert_eq!(c.get_room_header(&1).unwrap().status, RoomStatus::Ended);
+    assert!(c.get_room_header(&2).is_none());
+}
+
+#[test]
+fn player_rooms_track_joins() {
+    let e = Env::default(); e.mock_all_auths();
+    let (c, _, _, _, tokens) = initialize_contract_with_tokens(&e);
+    let host = Address::generate(&e);
+    let player = Address::generate(&e);
+    let t = tokens.get(0).unwrap();
+
+    mint_tokens_for_users(&e, &t, &[player.clone()], 10_000_000);
+    for room_id in [1u32, 2, 3] {
+        c.init_pool_room(&room_id, &host, &t, &1_000_000, &None, &2000, &100, &None, &None, &None);
+    }
+    assert_eq!(c.get_player_rooms(&player).len(), 0);
+
+    c.join_room(&3, &player, &String::from_str(&e, "P"), &0);
+    c.join_room(&1, &player, &String::from_str(&e, "P"), &0);
+    assert_eq!(c.get_player_rooms(&player), Vec::from_array(&e, [3u32, 1]));
+
+    // A rejected join leaves the list alone
+    assert!(c.try_join_room(&1, &player, &String::from_str(&e, "Again"), &0).is_err());
+    assert_eq!(c.get_player_rooms(&player).len(), 2);
+
+    // Ended rooms stay as history; a cancelled room is dropped before the refund is claimed
+    c.end_room(&3, &Some(player.clone()), &None, &None);
+    c.cancel_room(&1);
+    assert_eq!(c.get_player_rooms(&player), Vec::from_array(&e, [3u32]));
+    c.claim_refund(&1, &player);
+    assert_eq!(c.get_player_rooms(&player), Vec::from_array(&e, [3u32]));
+}
+
+#[test]
+fn room_config_hash_changes_after_join() {
+    let e = Env::default(); e.mock_all_auths();
+    let (c, _, _, _, tokens) = initialize_contract_with_tokens(&e);
+    let host = Address::generate(&e);
+    let player = Address::generate(&e);
+    let t = tokens.get(0).unwrap();
+    mint_tokens_for_users(&e, &t, &[player.clone()], 10_000_000);
+    c.init_pool_room(&1, &host, &t, &1_000_000, &None, &2000, &100, &None, &None, &None);
+    c.init_pool_room(&2, &host, &t, &1_000_000, &None, &2000, &100, &None, &None, &None);
+
+    let before = c.get_room_config_hash(&1).unwrap();
+    assert_eq!(c.get_room_config_hash(&1), Some(before.clone()));
+    assert_ne!(c.get_room_config_hash(&2), Some(before.clone()));
+    assert!(c.get_room_config_hash(&3).is_none());
+
+    c.join_room(&1, &player, &String::from_str(&e, "P"), &0);
+    assert_ne!(c.get_room_config_hash(&1), Some(before));
+}
+
+#[test]
+fn list_rooms_pages_over_open_rooms() {
+    let e = Env::default(); e.mock_all_auths();
+    let (c, _, _, _, tokens) = initialize_contract_with_tokens(&e);
+    let host = Address::generate(&e);
+    let t = tokens.get(0).unwrap();
+
+    for room_id in [1u32, 2, 3] {
+        c.init_pool_room(&room_id, &host, &t, &1_000_000, &None, &2000, &100, &None, &None, &None);
+    }
+
+    let first = c.list_rooms(&0, &2);
+    assert_eq!(first.len(), 2);
+    assert_eq!(first.get(0).unwrap().room_id, 1);
+    assert_eq!(first.get(1).unwrap().room_id, 2);
+
+    let second = c.list_rooms(&2, &2);
+    assert_eq!(second.len(), 1);
+    assert_eq!(second.get(0).unwrap().room_id, 3);
+    assert_eq!(second.get(0).unwrap().host, host);
+
+    // Closed rooms drop out of the listing
+    c.cancel_room(&2);
+    let open = c.list_rooms(&0, &10);
+    assert_eq!(open.len(), 2);
+    assert_eq!(open.get(1).unwrap().room_id, 3);
+    assert_eq!(c.list_rooms(&1, &1).get(0).unwrap().room_id, 3);
+    assert_eq!(c.list_rooms(&2, &10).len(), 0);
+
+    // Oversized limits are clamped rather than rejected
+    assert_eq!(c.list_rooms(&0, &u32::MAX).len(), 2);
+    assert_eq!(c.list_rooms(&u32::MAX, &u32::MAX).len(), 0);
+}
+
+#[test]
+fn screen_name_limit_is_configurable() {
+    let e = Env::default(); e.mock_all_auths();
+    let (c, _, _, _, tokens) = initialize_contract_with_tokens(&e);
+    let host = Address::generate(&e);
+    let player = Address::generate(&e);
+    let t = tokens.get(0).unwrap();
+    let name = String::from_str(&e, "TwentyFiveCharacterName25");
+
+    mint_tokens_for_users(&e, &t, &[player.clone()], 10_000_000);
+    c.init_pool_room(&1, &host, &t, &1_000_000, &None, &2000, &100, &None, &None, &None);
+
+    // Default limit is 20
+    let r = c.try_join_room(&1, &player, &name, &0);
+    assert_eq!(r, Err(Ok(QuizError::InvalidScreenName)));
+
+    // Limit must stay within 1..=64
+    assert_eq!(c.try_set_max_screen_name_len(&0), Err(Ok(QuizError::InvalidScreenName)));
+    assert_eq!(c.try_set_max_screen_name_len(&65), Err(Ok(QuizError::InvalidScreenName)));
+
+    c.set_max_screen_name_len(&30);
+    assert!(c.is_screen_name_available(&1, &name));
+    c.join_room(&1, &player, &name, &0);
+    assert_eq!(c.get_room_config(&1).unwrap().player_count(), 1);
+}
+
+#[test]
+fn get_admin_follows_accepted_transfer() {
+    let e = Env::default(); e.mock_all_auths();
+    let (c, _, admin, _, _) = initialize_contract_with_tokens(&e);
+    let new_admin = Address::generate(&e);
+    assert_eq!(c.get_admin(), admin);
+
+    // Pending until accepted
+    c.transfer_admin(&new_admin);
+    assert_eq!(c.get_admin(), admin);
+
+    c.accept_admin();
+    assert_eq!(c.get_admin(), new_admin);
+}
+
+#[test]
+fn rejected_winner_transfer_becomes_claimable() {
+    let e = Env::default(); e.mock_all_auths();
+    let (c, _, admin, platform, _) = initialize_contract_with_tokens(&e);
+    let host = Address::generate(&e);
+    let p1 = Address::generate(&e);
+    let p2 = Address::generate(&e);
+    let p3 = Address::generate(&e);
+
+    // Issuer allows freezing trustlines; a frozen trustline rejects incoming transfers
+    let asset = e.register_stellar_asset_contract_v2(admin.clone());
+    asset.issuer().set_flag(IssuerFlags::RevocableFlag);
+    let t = asset.address();
+    c.add_approved_token(&t, &String::from_str(&e, "FRZ"), &String::from_str(&e, "Freezable"));
+    let tc = TokenClient::new(&e, &t);
+    let sac = StellarAssetClient::new(&e, &t);
+
+    mint_tokens_for_users(&e, &t, &[p1.clone(), p2.clone(), p3.clone()], 10_000_000);
+    c.init_pool_room(&1, &host, &t, &1_000_000, &None, &2000, &50, &Some(30), &Some(20), &None);
+    c.join_room(&1, &p1, &String::from_str(&e, "P1"), &0);
+    c.join_room(&1, &p2, &String::from_str(&e, "P2"), &0);
+    c.join_room(&1, &p3, &String::from_str(&e, "P3"), &0);
+
+    sac.set_authorized(&p2, &false);
+    let p1_before = tc.balance(&p1);
+    let p3_before = tc.balance(&p3);
+    c.end_room(&1, &Some(p1.clone()), &Some(p2.clone()), &Some(p3.clone()));
+
+    // Pool 3_000_000, prize pool 20% = 600_000: 50/30/20
+    assert_eq!(tc.balance(&p1) - p1_before, 300_000);
+    assert_eq!(tc.balance(&p3) - p3_before, 120_000);
+    assert_eq!(tc.balance(&platform), 600_000);
+    assert_eq!(c.get_pending_payout(&p2, &t), 180_000);
+    assert_eq!(c.get_distribution_receipt(&1).unwrap().prize, 600_000);
+
+    // Still frozen: the claim fails and the payout stays owed
+    assert_eq!(c.try_claim_pending_payout(&p2, &t), Err(Ok(QuizError::AssetTransferFailed)));
+    assert_eq!(c.get_pending_payout(&p2, &t), 180_000);
+
+    sac.set_authorized(&p2, &true);
+    let p2_before = tc.balance(&p2);
+    assert_eq!(c.claim_pending_payout(&p2, &t), 180_000);
+    assert_eq!(tc.balance(&p2) - p2_before, 180_000);
+    assert_eq!(c.get_pending_payout(&p2, &t), 0);
+    assert_eq!(c.try_claim_pending_payout(&p2, &t), Err(Ok(QuizError::InsufficientBalance)));
+}
+
+#[test]
+fn reserved_id_range_confines_host() {
+    let e = Env::default(); e.mock_all_auths();
+    let (c, _, _, _, tokens) = initialize_contract_with_tokens(&e);
+    let host = Address::generate(&e);
+    let other = Address::generate(&e);
+    let t = tokens.get(0).unwrap();
+
+    // No reservations: any id works
+    c.init_pool_room(&1, &other, &t, &1_000_000, &None, &2000, &100, &None, &None, &None);
+
+    c.reserve_id_range(&host, &100, &199);
+    assert_eq!(c.get_id_ranges(&host), Vec::from_array(&e, [(100u32, 199u32)]));
+    assert_eq!(c.try_reserve_id_range(&other, &150, &250), Err(Ok(QuizError::Unauthorized)));
+    assert_eq!(c.try_reserve_id_range(&other, &300, &299), Err(Ok(QuizError::InvalidTotalAllocation)));
+
+    let r = c.try_init_pool_room(&5, &host, &t, &1_000_000, &None, &2000, &100, &None, &None, &None);
+    assert_eq!(r, Err(Ok(QuizError::Unauthorized)));
+    c.init_pool_room(&100, &host, &t, &1_000_000, &None, &2000, &100, &None, &None, &None);
+
+    // Other hosts stay out of the reserved range but keep the rest
+    let r = c.try_init_pool_room(&150, &other, &t, &1_000_000, &None, &2000, &100, &None, &None, &None);
+    assert_eq!(r, Err(Ok(QuizError::Unauthorized)));
+    c.init_pool_room(&5, &other, &t, &1_000_000, &None, &2000, &100, &None, &None, &None);
+}
+
+#[test]
+fn max_possible_prize_is_first_place_share() {
+    let e = Env::default(); e.mock_all_auths();
+    let (c, _, _, _, tokens) = initialize_contract_with_tokens(&e);
+    let host = Address::generate(&e);
+    let p1 = Address::generate(&e);
+    let p2 = Address::generate(&e);
+    let fee = tokens.get(0).unwrap();
+    let prize = tokens.get(1).unwrap();
+
+    mint_tokens_for_users(&e, &fee, &[p1.clone(), p2.clone()], 10_000_000);
+    mint_tokens_for_users(&e, &prize, &[host.clone()], 5_000_000);
+
+    c.init_pool_room(&1, &host, &fee, &1_000_000, &None, &2000, &60, &Some(40), &None, &None);
+    assert_eq!(c.get_max_possible_prize(&1), Some(0));
+    c.join_room(&1, &p1, &String::from_str(&e, "P1"), &0);
+    c.join_room(&1, &p2, &String::from_str(&e, "P2"), &0);
+
+    // 60% of the 400_000 prize pool
+    let prize_amount = c.get_prize_pool_amount(&1).unwrap();
+    assert_eq!(prize_amount, 400_000);
+    assert_eq!(c.get_max_possible_prize(&1), Some(prize_amount * 60 / 100));
+
+    // Asset rooms report the biggest escrowed prize
+    let prizes = Vec::from_array(&e, [
+        PrizeAsset { contract_id: prize.clone(), amount: 1_000_000 },
+        PrizeAsset { contract_id: prize.clone(), amount: 3_000_000 },
+    ]);
+    c.init_asset_room(&2, &host, &fee, &1_000_000, &None, &prizes, &2);
+    assert_eq!(c.get_max_possible_prize(&2), Some(3_000_000));
+    assert_eq!(c.get_max_possible_prize(&3), None);
+}
+
+#[test]
+fn empty_room_migrates_off_disabled_token() {
+    let e = Env::default(); e.mock_all_auths();
+    let (c, contract_addr, _, _, tokens) = initialize_contract_with_tokens(&e);
+    let host = Address::generate(&e);
+    let p = Address::generate(&e);
+    let old_token = tokens.get(0).unwrap();
+    let new_token = tokens.get(1).unwrap();
+
+    mint_tokens_for_users(&e, &new_token, &[p.clone()], 10_000_000);
+    c.init_pool_room(&1, &host, &old_token, &1_000_000, &None, &2000, &100, &None, &None, &None);
+    c.enable_disable_token(&old_token, &false);
+
+    // Only to an approved, enabled token
+    assert_eq!(c.try_migrate_room_token(&1, &old_token), Err(Ok(QuizError::TokenNotApproved)));
+    c.migrate_room_token(&1, &new_token);
+    assert_eq!(c.get_room_config(&1).unwrap().fee_token(), &new_token);
+    assert_eq!(c.get_room_header(&1).unwrap().fee_token, new_token);
+
+    c.join_room(&1, &p, &String::from_str(&e, "P"), &0);
+    assert_eq!(TokenClient::new(&e, &new_token).balance(&contract_addr), 1_000_000);
+
+    // Not once anyone has joined
+    let third = tokens.get(2).unwrap();
+    assert_eq!(c.try_migrate_room_token(&1, &third), Err(Ok(QuizError::PlayerAlreadyJoined)));
+}
+
+#[test]
+fn prize_ranks_must_fill_in_order() {
+    let e = Env::default(); e.mock_all_auths();
+    let (c, _, _, _, tokens) = initialize_contract_with_tokens(&e);
+    let host = Address::generate(&e);
+    let t = tokens.get(0).unwrap();
+
+    // First place must pay
+    let r = c.try_init_pool_room(&1, &host, &t, &1_000_000, &None, &2000, &0, &Some(100), &None, &None);
+    assert_eq!(r, Err(Ok(QuizError::InvalidPrizeSplit)));
+
+    // Third can't pay while second doesn't
+    let r = c.try_init_pool_room(&1, &host, &t, &1_000_000, &None, &2000, &60, &None, &Some(40), &None);
+    assert_eq!(r, Err(Ok(QuizError::InvalidPrizeSplit)));
+    let r = c.try_init_pool_room(&1, &host, &t, &1_000_000, &None, &2000, &60, &Some(0), &Some(40), &None);
+    assert_eq!(r, Err(Ok(QuizError::InvalidPrizeSplit)));
+
+    c.init_pool_room(&1, &host, &t, &1_000_000, &None, &2000, &50, &Some(30), &Some(20), &None);
+}
+
+#[test]
+fn total_held_sums_open_pools_and_escrow() {
+    let e = Env::default(); e.mock_all_auths();
+    let (c, _, _, _, tokens) = initialize_contract_with_tokens(&e);
+    let host = Address::generate(&e);
+    let p1 = Address::generate(&e);
+    let p2 = Address::generate(&e);
+    let t = tokens.get(0).unwrap();
+    let other = tokens.get(1).unwrap();
+
+    mint_tokens_for_users(&e, &t, &[p1.clone(), p2.clone(), host.clone()], 10_000_000);
+    c.init_pool_room(&1, &host, &t, &1_000_000, &None, &2000, &100, &None, &None, &None);
+    c.init_pool_room(&2, &host, &t, &2_000_000, &None, &2000, &100, &None, &None, &None);
+    assert_eq!(c.get_total_held(&t), 0);
+
+    c.join_room(&1, &p1, &String::from_str(&e, "P1"), &500_000);
+    c.join_room(&1, &p2, &String::from_str(&e, "P2"), &0);
+    c.join_room(&2, &p1, &String::from_str(&e, "P1"), &0);
+    let pools = c.get_room_config(&1).unwrap().total_pool() + c.get_room_config(&2).unwrap().total_pool();
+    assert_eq!(pools, 4_500_000);
+    assert_eq!(c.get_total_held(&t), pools);
+    assert_eq!(c.get_total_held(&other), 0);
+
+    // Escrowed prizes in the same token count too
+    let prizes = Vec::from_array(&e, [PrizeAsset { contract_id: t.clone(), amount: 3_000_000 }]);
+    c.init_asset_room(&3, &host, &other, &1_000_000, &None, &prizes, &1);
+    assert_eq!(c.get_total_held(&t), pools + 3_000_000);
+
+    // Settled and cancelled rooms drop out
+    c.end_room(&1, &Some(p1.clone()), &None, &None);
+    assert_eq!(c.get_total_held(&t), 2_000_000 + 3_000_000);
+    c.cancel_room(&2);
+    assert_eq!(c.get_total_held(&t), 3_000_000);
+}
+
+// Ends a one-player room while the winner's trustline is frozen, then thaws it; returns (client, token, winner)
+fn room_with_deferred_payout(e: &Env) -> (QuizRoomContractClient<'_>, Address, Address) {
+    let (c, _, admin, _, _) = initialize_contract_with_tokens(e);
+    let host = Address::generate(e);
+    let winner = Address::generate(e);
+
+    let asset = e.register_stellar_asset_contract_v2(admin.clone());
+    asset.issuer().set_flag(IssuerFlags::RevocableFlag);
+    let t = asset.address();
+    c.add_approved_token(&t, &String::from_str(e, "FRZ"), &String::from_str(e, "Freezable"));
+    mint_tokens_for_users(e, &t, &[winner.clone()], 10_000_000);
+
+    c.init_pool_room(&1, &host, &t, &1_000_000, &None, &2000, &100, &None, &None, &None);
+    c.join_room(&1, &winner, &String::from_str(e, "W"), &0);
+    StellarAssetClient::new(e, &t).set_authorized(&winner, &false);
+    c.end_room(&1, &Some(winner.clone()), &None, &None);
+    StellarAssetClient::new(e, &t).set_authorized(&winner, &true);
+    (c, t, winner)
+}
+
+#[test]
+fn rejected_charity_transfer_is_claimable() {
+    let e = Env::default(); e.mock_all_auths();
+    let (c, contract_addr, admin, _, _) = initialize_contract_with_tokens(&e);
+    let charity = c.get_charity_wallet();
+    let host = Address::generate(&e);
+    let winner = Address::generate(&e);
+
+    let asset = e.register_stellar_asset_contract_v2(admin.clone());
+    asset.issuer().set_flag(IssuerFlags::RevocableFlag);
+    let t = asset.address();
+    let tc = TokenClient::new(&e, &t);
+    c.add_approved_token(&t, &String::from_str(&e, "FRZ"), &String::from_str(&e, "Freezable"));
+    mint_tokens_for_users(&e, &t, &[winner.clone()], 10_000_000);
+
+    c.init_pool_room(&1, &host, &t, &1_000_000, &None, &2000, &100, &None, &None, &None);
+    c.join_room(&1, &winner, &String::from_str(&e, "W"), &0);
+    StellarAssetClient::new(&e, &t).set_authorized(&charity, &false);
+    c.end_room(&1, &Some(winner.clone()), &None, &None);
+
+    // The winner is paid and the charity share waits in the contract
+    assert_eq!(tc.balance(&winner), 10_000_000 - 1_000_000 + 200_000);
+    assert_eq!(c.get_pending_payout(&charity, &t), 600_000);
+    assert_eq!(tc.balance(&contract_addr), 600_000);
+
+    StellarAssetClient::new(&e, &t).set_authorized(&charity, &true);
+    assert_eq!(c.claim_pending_payout(&charity, &t), 600_000);
+    assert_eq!(tc.balance(&charity), 600_000);
+}
+
+#[test]
+fn claimant_auth_is_configurable() {
+    // Default: the winner must sign their own claim
+    let e = Env::default(); e.mock_all_auths();
+    let (c, t, winner) = room_with_deferred_payout(&e);
+    assert_eq!(c.claim_pending_payout(&winner, &t), 200_000);
+    assert!(e.auths().iter().any(|(addr, _)| *addr == winner));
+
+    // Gasless: anyone may trigger it, and the funds still go to the winner
+    let e = Env::default(); e.mock_all_auths();
+    let (c, t, winner) = room_with_deferred_payout(&e);
+    c.set_require_claimant_auth(&false);
+    let before = TokenClient::new(&e, &t).balance(&winner);
+    assert_eq!(c.claim_pending_payout(&winner, &t), 200_000);
+    assert!(!e.auths().iter().any(|(addr, _)| *addr == winner));
+    assert_eq!(TokenClient::new(&e, &t).balance(&winner) - before, 200_000);
+}
+
+#[test]
+fn unclaimed_payout_is_swept_to_charity_after_deadline() {
+    let e = Env::default(); e.mock_all_auths();
+    let (c, t, winner) = room_with_deferred_payout(&e);
+    c.set_unclaimed_deadline(&100);
+    assert_eq!(c.try_sweep_unclaimed(&1), Err(Ok(QuizError::Unauthorized)));
+
+    let charity = c.get_charity_wallet();
+    let before = TokenClient::new(&e, &t).balance(&charity);
+    e.ledger().with_mut(|l| l.sequence_number += 101);
+    c.sweep_unclaimed(&1);
+    assert_eq!(TokenClient::new(&e, &t).balance(&charity) - before, 200_000);
+    assert_eq!(c.get_pending_payout(&winner, &t), 0);
+    assert_eq!(c.try_claim_pending_payout(&winner, &t), Err(Ok(QuizError::InsufficientBalance)));
+    assert_eq!(c.try_sweep_unclaimed(&1), Err(Ok(QuizError::InsufficientBalance)));
+
+    // A payout claimed in time leaves nothing to sweep
+    let e = Env::default(); e.mock_all_auths();
+    let (c, t, winner) = room_with_deferred_payout(&e);
+    c.claim_pending_payout(&winner, &t);
+    e.ledger().with_mut(|l| l.sequence_number += 1_000_000);
+    assert_eq!(c.try_sweep_unclaimed(&1), Err(Ok(QuizError::InsufficientBalance)));
+}
+
+#[test]
+fn oversize_prize_asset_is_rejected() {
+    let e = Env::default(); e.mock_all_auths();
+    let (c, _, _, _, tokens) = initialize_contract_with_tokens(&e);
+    let host = Address::generate(&e);
+    let fee = tokens.get(0).unwrap();
+    let prize = tokens.get(1).unwrap();
+    mint_tokens_for_users(&e, &prize, &[host.clone()], 5_000_000);
+
+    assert_eq!(c.try_set_max_prize_asset_amount(&0), Err(Ok(QuizError::InsufficientAmount)));
+    assert_eq!(c.try_set_max_prize_asset_amount(&i128::MAX), Err(Ok(QuizError::AmountTooLarge)));
+    c.set_max_prize_asset_amount(&2_000_000);
+
+    let oversize = Vec::from_array(&e, [PrizeAsset { contract_id: prize.clone(), amount: 2_000_001 }]);
+    let r = c.try_init_asset_room(&1, &host, &fee, &1_000_000, &None, &oversize, &1);
+    assert_eq!(r, Err(Ok(QuizError::AmountTooLarge)));
+
+    let at_cap = Vec::from_array(&e, [PrizeAsset { contract_id: prize.clone(), amount: 2_000_000 }]);
+    c.init_asset_room(&1, &host, &fee, &1_000_000, &None, &at_cap, &1);
+    assert_eq!(TokenClient::new(&e, &prize).balance(&host), 3_000_000);
+}
+
+#[test]
+fn escrowed_prizes_report_token_decimals() {
+    let e = Env::default(); e.mock_all_auths();
+    let (c, _, _, _, tokens) = initialize_contract_with_tokens(&e);
+    let host = Address::generate(&e);
+    let fee = tokens.get(0).unwrap();
+    let prize_a = tokens.get(1).unwrap();
+    let prize_b = tokens.get(2).unwrap();
+
+    mint_tokens_for_users(&e, &prize_a, &[host.clone()], 5_000_000);
+    mint_tokens_for_users(&e, &prize_b, &[host.clone()], 5_000_000);
+    let prizes = Vec::from_array(&e, [
+        PrizeAsset { contract_id: prize_a.clone(), amount: 3_000_000 },
+        PrizeAsset { contract_id: prize_b.clone(), amount: 1_000_000 },
+    ]);
+    c.init_asset_room(&1, &host, &fee, &1_000_000, &None, &prizes, &2);
+
+    let escrowed = c.get_escrowed_prizes(&1);
+    assert_eq!(escrowed.len(), 2);
+    let (first, first_decimals) = escrowed.get(0).unwrap();
+    let (second, second_decimals) = escrowed.get(1).unwrap();
+    assert_eq!((first.contract_id, first.amount), (prize_a.clone(), 3_000_000));
+    assert_eq!(first_decimals, TokenClient::new(&e, &prize_a).decimals());
+    assert_eq!((second.contract_id, second.amount), (prize_b.clone(), 1_000_000));
+    assert_eq!(second_decimals, TokenClient::new(&e, &prize_b).decimals());
+
+    // Nothing left in escrow once the room is cancelled
+    c.cancel_room(&1);
+    assert_eq!(c.get_escrowed_prizes(&1).len(), 0);
+}
+
+#[test]
+fn paused_room_blocks_joins_and_end_only_for_itself() {
+    let e = Env::default(); e.mock_all_auths();
+    let (c, _, admin, _, tokens) = initialize_contract_with_tokens(&e);
+    let host = Address::generate(&e);
+    let p1 = Address::generate(&e);
+    let p2 = Address::generate(&e);
+    let stranger = Address::generate(&e);
+    let t = tokens.get(0).unwrap();
+
+    mint_tokens_for_users(&e, &t, &[p1.clone(), p2.clone()], 10_000_000);
+    c.init_pool_room(&1, &host, &t, &1_000_000, &None, &2000, &100, &None, &None, &None);
+    c.init_pool_room(&2, &host, &t, &1_000_000, &None, &2000, &100, &None, &None, &None);
+    c.join_room(&1, &p1, &String::from_str(&e, "P1"), &0);
+
+    // Only the host or the admin may pause
+    assert_eq!(c.try_pause_room(&1, &stranger), Err(Ok(QuizError::Unauthorized)));
+    c.pause_room(&1, &host);
+    assert!(c.get_room_config(&1).unwrap().paused());
+
+    let r = c.try_join_room(&1, &p2, &String::from_str(&e, "P2"), &0);
+    assert_eq!(r, Err(Ok(QuizError::EmergencyPause)));
+    assert_eq!(c.try_end_room(&1, &Some(p1.clone()), &None, &None), Err(Ok(QuizError::EmergencyPause)));
+
+    // Other rooms carry on
+    c.join_room(&2, &p2, &String::from_str(&e, "P2"), &0);
+
+    c.unpause_room(&1, &admin);
+    c.join_room(&1, &p2, &String::from_str(&e, "P2"), &0);
+    c.end_room(&1, &Some(p1.clone()), &None, &None);
+}
+
+#[test]
+fn platform_and_charity_wallets_must_differ() {
+    let e = Env::default(); e.mock_all_auths();
+    let (c, _) = create_quiz_contract(&e);
+    let admin = Address::generate(&e);
+    let wallet = Address::generate(&e);
+    let charity = Address::generate(&e);
+
+    assert_eq!(c.try_initialize(&admin, &wallet, &wallet), Err(Ok(QuizError::InvalidAddress)));
+    c.initialize(&admin, &wallet, &charity);
+
+    // Nor can an update make them equal
+    assert_eq!(c.try_update_wallets(&None, &Some(wallet.clone())), Err(Ok(QuizError::InvalidAddress)));
+    assert_eq!(c.get_charity_wallet(), charity);
+}
+
+#[test]
+fn host_cannot_be_a_fee_wallet() {
+    let e = Env::default(); e.mock_all_auths();
+    let (c, _, _, platform, tokens) = initialize_contract_with_tokens(&e);
+    let charity = c.get_charity_wallet();
+    let t = tokens.get(0).unwrap();
+    let prize = tokens.get(1).unwrap();
+
+    for host in [platform, charity] {
+        let r = c.try_init_pool_room(&1, &host, &t, &1_000_000, &None, &2000, &100, &None, &None, &None);
+        assert_eq!(r, Err(Ok(QuizError::InvalidAddress)));
+
+        mint_tokens_for_users(&e, &prize, &[host.clone()], 1_000_000);
+        let prizes = Vec::from_array(&e, [PrizeAsset { contract_id: prize.clone(), amount: 1_000_000 }]);
+        let r = c.try_init_asset_room(&1, &host, &t, &1_000_000, &None, &prizes, &1);
+        assert_eq!(r, Err(Ok(QuizError::InvalidAddress)));
+    }
+    assert!(c.get_room_config(&1).is_none());
+}
+
+#[test]
+fn room_creation_without_tokens_reports_setup_error() {
+    let e = Env::default(); e.mock_all_auths();
+    let (c, _) = create_quiz_contract(&e);
+    let admin = Address::generate(&e);
+    let host = Address::generate(&e);
+    c.initialize(&admin, &Address::generate(&e), &Address::generate(&e));
+    let t = create_token_contract(&e, &admin);
+
+    let r = c.try_init_pool_room(&1, &host, &t, &1_000_000, &None, &2000, &100, &None, &None, &None);
+    assert_eq!(r, Err(Ok(QuizError::TokenNotFound)));
+    let prizes = Vec::from_array(&e, [PrizeAsset { contract_id: t.clone(), amount: 1_000_000 }]);
+    let r = c.try_init_asset_room(&2, &host, &t, &1_000_000, &None, &prizes, &1);
+    assert_eq!(r, Err(Ok(QuizError::TokenNotFound)));
+
+    // Once a token is configured, an unlisted one is reported as such
+    let listed = create_token_contract(&e, &admin);
+    c.add_approved_token(&listed, &String::from_str(&e, "USDC"), &String::from_str(&e, "USD Coin"));
+    let r = c.try_init_pool_room(&1, &host, &t, &1_000_000, &None, &2000, &100, &None, &None, &None);
+    assert_eq!(r, Err(Ok(QuizError::TokenNotApproved)));
+}